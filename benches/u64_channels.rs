@@ -31,10 +31,13 @@ fn mpsc_channel(n: u64) {
     t.join().unwrap();
 }
 
-fn disrustor_channel<S: Sequencer, F: FnOnce(&RingBuffer<i64>) -> S>(n: u64, b: u64, f: F) {
+fn disrustor_channel<S: Sequencer, F: FnOnce(&RingBuffer<i64>) -> S>(n: u64, b: u64, f: F)
+where
+    S::Barrier: 'static,
+{
     let capacity = 65536;
     let data: Arc<RingBuffer<i64>> = Arc::new(RingBuffer::new(capacity));
-    let mut sequencer = f(data.as_ref());
+    let sequencer = f(data.as_ref());
 
     let gating_sequence = vec![sequencer.get_cursor()];
     let barrier = sequencer.create_barrier(&gating_sequence);
@@ -50,7 +53,7 @@ fn disrustor_channel<S: Sequencer, F: FnOnce(&RingBuffer<i64>) -> S>(n: u64, b:
     for _ in 1..=n / b {
         let mut remainder = b as i64;
         while remainder > 0 {
-            let (start, end) = sequencer.next(remainder as usize);
+            let (start, end) = sequencer.next(remainder as usize).unwrap();
             let count = end - start + 1;
             remainder -= count;
             for sequence in start..=end {
@@ -62,7 +65,7 @@ fn disrustor_channel<S: Sequencer, F: FnOnce(&RingBuffer<i64>) -> S>(n: u64, b:
     }
 
     sequencer.drain();
-    handle.join();
+    handle.join().unwrap();
     assert_eq!(counter, n);
 }
 
@@ -115,6 +118,28 @@ fn criterion_benchmark(c: &mut Criterion) {
     }
     group.finish();
 
+    let mut group = c.benchmark_group("single_producer_lite_blocking");
+    group.throughput(Throughput::Elements(N));
+    group.warm_up_time(Duration::from_secs(10));
+    group.sampling_mode(SamplingMode::Flat);
+    for batch_size in [1, 10, 50, 100, 1000] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(batch_size),
+            &batch_size,
+            |b, batch_size| {
+                b.iter(|| {
+                    disrustor_channel(black_box(N), *batch_size, |d| {
+                        SingleProducerSequencer::new(
+                            d.buffer_size(),
+                            LiteBlockingWaitStrategy::new(),
+                        )
+                    })
+                });
+            },
+        );
+    }
+    group.finish();
+
     let mut group = c.benchmark_group("multi_producer_spinning");
     group.throughput(Throughput::Elements(N));
     group.warm_up_time(Duration::from_secs(10));
@@ -138,7 +163,7 @@ fn criterion_benchmark(c: &mut Criterion) {
     group.throughput(Throughput::Elements(N));
     group.warm_up_time(Duration::from_secs(10));
     group.sampling_mode(SamplingMode::Flat);
-    for batch_size in [10, 50, 100, 1000] {
+    for batch_size in [1, 10, 50, 100, 1000] {
         group.bench_with_input(
             BenchmarkId::from_parameter(batch_size),
             &batch_size,
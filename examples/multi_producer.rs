@@ -37,9 +37,9 @@ impl EventHandler<u32> for Checker {
     }
 }
 
-fn follow_sequence<W: WaitStrategy + 'static>() {
+fn follow_sequence<W: WaitStrategy + 'static>(wait_strategy: W) {
     let (executor, producer) = DisrustorBuilder::with_ring_buffer(128)
-        .with_wait_strategy::<W>()
+        .with_wait_strategy(wait_strategy)
         .with_multi_producer()
         .with_barrier(|b| {
             b.handle_events_mut(Doubler {});
@@ -47,10 +47,10 @@ fn follow_sequence<W: WaitStrategy + 'static>() {
         .with_barrier(|b| {
             b.handle_events(Checker {});
         })
-        .build();
+        .build()
+        .unwrap();
 
     let handle = executor.spawn();
-    let producer = std::sync::Arc::new(producer);
     let producer1 = producer.clone();
     let producer2 = producer.clone();
 
@@ -58,28 +58,30 @@ fn follow_sequence<W: WaitStrategy + 'static>() {
         for i in 1..=MAX / 10 {
             let range = ((i - 1) * 20)..=((i - 1) * 20 + 19);
             let items: Vec<_> = range.collect();
-            producer1.write(items, |d, seq, _| {
-                *d = seq as u32;
-            });
+            producer1
+                .write(items, |d, seq, _| {
+                    *d = seq as u32;
+                })
+                .unwrap();
         }
     });
     let p2 = std::thread::spawn(move || {
         for i in 1..=MAX / 10 {
             let range = ((i - 1) * 20)..=((i - 1) * 20 + 19);
             let items: Vec<_> = range.collect();
-            producer2.write(items, |d, seq, _| {
-                *d = seq as u32;
-            });
+            producer2
+                .write(items, |d, seq, _| {
+                    *d = seq as u32;
+                })
+                .unwrap();
         }
     });
 
     p1.join().unwrap();
     p2.join().unwrap();
-    if let Ok(producer) = std::sync::Arc::try_unwrap(producer) {
-        producer.drain();
-    }
+    producer.drain();
 
-    handle.join();
+    handle.join().unwrap();
 }
 
 fn main() {
@@ -101,8 +103,8 @@ fn main() {
         .unwrap();
 
     info!("running blocking wait strategy");
-    follow_sequence::<BlockingWaitStrategy>();
+    follow_sequence(BlockingWaitStrategy::new());
 
     info!("running spinning wait strategy");
-    follow_sequence::<SpinLoopWaitStrategy>();
+    follow_sequence(SpinLoopWaitStrategy::new());
 }
@@ -37,9 +37,9 @@ impl EventHandler<u32> for Checker {
     }
 }
 
-fn follow_sequence<W: WaitStrategy + 'static>() {
+fn follow_sequence<W: WaitStrategy + 'static>(wait_strategy: W) {
     let (executor, producer) = DisrustorBuilder::with_ring_buffer(128)
-        .with_wait_strategy::<W>()
+        .with_wait_strategy(wait_strategy)
         .with_single_producer()
         .with_barrier(|b| {
             b.handle_events_mut(Doubler {});
@@ -47,19 +47,22 @@ fn follow_sequence<W: WaitStrategy + 'static>() {
         .with_barrier(|b| {
             b.handle_events(Checker {});
         })
-        .build();
+        .build()
+        .unwrap();
 
     let handle = executor.spawn();
     for i in 1..=MAX / 20 {
         let range = ((i - 1) * 20)..=((i - 1) * 20 + 19);
         let items: Vec<_> = range.collect();
-        producer.write(items, |d, _, v| {
-            *d = *v as u32;
-        });
+        producer
+            .write(items, |d, _, v| {
+                *d = *v as u32;
+            })
+            .unwrap();
     }
 
     producer.drain();
-    handle.join();
+    handle.join().unwrap();
 }
 
 fn main() {
@@ -81,8 +84,8 @@ fn main() {
         .unwrap();
 
     info!("running blocking wait strategy");
-    follow_sequence::<BlockingWaitStrategy>();
+    follow_sequence(BlockingWaitStrategy::new());
 
     info!("running spinning wait strategy");
-    follow_sequence::<SpinLoopWaitStrategy>();
+    follow_sequence(SpinLoopWaitStrategy::new());
 }
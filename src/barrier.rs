@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::{prelude::*, utils::*};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
@@ -8,31 +8,84 @@ pub struct ProcessingSequenceBarrier<W: WaitStrategy> {
     gating_sequences: Vec<Arc<AtomicSequence>>,
     wait_strategy: Arc<W>,
     is_alerted: Arc<AtomicBool>,
+    is_halted: Arc<AtomicBool>,
+    invalid_slots: Arc<InvalidSlots>,
+    wakers: Arc<WakerSet>,
 }
 
 impl<W: WaitStrategy> ProcessingSequenceBarrier<W> {
     pub fn new(
         wait_strategy: Arc<W>,
         gating_sequences: Vec<Arc<AtomicSequence>>,
-        is_alerted: Arc<AtomicBool>,
+        is_halted: Arc<AtomicBool>,
+        invalid_slots: Arc<InvalidSlots>,
+        wakers: Arc<WakerSet>,
     ) -> Self {
         ProcessingSequenceBarrier {
             wait_strategy,
             gating_sequences,
-            is_alerted,
+            is_alerted: Default::default(),
+            is_halted,
+            invalid_slots,
+            wakers,
+        }
+    }
+}
+
+impl<W: WaitStrategy> Clone for ProcessingSequenceBarrier<W> {
+    fn clone(&self) -> Self {
+        ProcessingSequenceBarrier {
+            gating_sequences: self.gating_sequences.clone(),
+            wait_strategy: self.wait_strategy.clone(),
+            is_alerted: self.is_alerted.clone(),
+            is_halted: self.is_halted.clone(),
+            invalid_slots: self.invalid_slots.clone(),
+            wakers: self.wakers.clone(),
         }
     }
 }
 
 impl<W: WaitStrategy> SequenceBarrier for ProcessingSequenceBarrier<W> {
-    fn wait_for(&self, sequence: Sequence) -> Option<Sequence> {
+    fn wait_for(&self, sequence: Sequence) -> Result<Sequence, WaitError> {
+        if self.is_alerted() {
+            return Err(WaitError::Alerted);
+        }
+
         self.wait_strategy
             .wait_for(sequence, &self.gating_sequences, || {
-                self.is_alerted.load(Ordering::Relaxed)
+                self.is_alerted.load(Ordering::Relaxed) || self.is_halted.load(Ordering::Relaxed)
             })
     }
 
+    fn available(&self) -> Sequence {
+        min_cursor_sequence(&self.gating_sequences)
+    }
+
+    fn is_invalid(&self, sequence: Sequence) -> bool {
+        self.invalid_slots.is_invalid(sequence)
+    }
+
+    fn is_alerted(&self) -> bool {
+        self.is_alerted.load(Ordering::Acquire) || self.is_halted.load(Ordering::Acquire)
+    }
+
+    #[cfg(feature = "async")]
+    fn register_waker(&self, waker: &std::task::Waker) {
+        self.wakers.register(waker);
+    }
+
     fn signal(&self) {
         self.wait_strategy.signal();
+        self.wakers.wake_all();
+    }
+
+    fn alert(&self) {
+        self.is_alerted.store(true, Ordering::SeqCst);
+        self.signal();
+    }
+
+    fn halt(&self) {
+        self.is_halted.store(true, Ordering::SeqCst);
+        self.signal();
     }
 }
@@ -1,5 +1,6 @@
-use crate::prelude::*;
+use crate::{exception::*, prelude::*, utils::*};
 use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::Arc;
 
 pub struct BatchEventProcessor;
@@ -9,54 +10,87 @@ impl BatchEventProcessor {
     where
         T: Send + 'a,
         E: EventHandler<T> + Send + 'a,
+    {
+        Self::create_with_exception_handler(handler, FatalExceptionHandler)
+    }
+
+    pub fn create_mut<'a, E, T>(handler: E) -> impl EventProcessorMut<'a, T>
+    where
+        T: Send + 'a,
+        E: EventHandlerMut<T> + Send + 'a,
+    {
+        Self::create_mut_with_exception_handler(handler, FatalExceptionHandler)
+    }
+
+    pub fn create_with_exception_handler<'a, E, X, T>(
+        handler: E,
+        exception_handler: X,
+    ) -> impl EventProcessor<'a, T>
+    where
+        T: Send + 'a,
+        E: EventHandler<T> + Send + 'a,
+        X: ExceptionHandler<T> + Send + 'a,
     {
         Processor {
             handler,
+            exception_handler,
             cursor: Default::default(),
+            counters: Default::default(),
             _marker: Default::default(),
         }
     }
 
-    pub fn create_mut<'a, E, T>(handler: E) -> impl EventProcessorMut<'a, T>
+    pub fn create_mut_with_exception_handler<'a, E, X, T>(
+        handler: E,
+        exception_handler: X,
+    ) -> impl EventProcessorMut<'a, T>
     where
         T: Send + 'a,
         E: EventHandlerMut<T> + Send + 'a,
+        X: ExceptionHandler<T> + Send + 'a,
     {
         ProcessorMut {
             handler,
+            exception_handler,
             cursor: Default::default(),
+            counters: Default::default(),
             _marker: Default::default(),
         }
     }
 }
 
-struct Processor<E, T> {
+struct Processor<E, X, T> {
     handler: E,
+    exception_handler: X,
     cursor: Arc<AtomicSequence>,
+    counters: Arc<ProcessorCounters>,
     _marker: PhantomData<T>,
 }
 
-struct ProcessorMut<E, T> {
+struct ProcessorMut<E, X, T> {
     handler: E,
+    exception_handler: X,
     cursor: Arc<AtomicSequence>,
+    counters: Arc<ProcessorCounters>,
     _marker: PhantomData<T>,
 }
 
-struct RunnableProcessor<E, T, D: DataProvider<T>, B: SequenceBarrier> {
-    processor: Processor<E, T>,
+struct RunnableProcessor<E, X, T, D: DataProvider<T>, B: SequenceBarrier> {
+    processor: Processor<E, X, T>,
     data_provider: Arc<D>,
     barrier: B,
 }
 
-struct RunnableProcessorMut<E, T, D: DataProvider<T>, B: SequenceBarrier> {
-    processor: ProcessorMut<E, T>,
+struct RunnableProcessorMut<E, X, T, D: DataProvider<T>, B: SequenceBarrier> {
+    processor: ProcessorMut<E, X, T>,
     data_provider: Arc<D>,
     barrier: B,
 }
 
-impl<'a, E, T> EventProcessorMut<'a, T> for Processor<E, T>
+impl<'a, E, X, T> EventProcessorMut<'a, T> for Processor<E, X, T>
 where
     E: EventHandler<T> + Send + 'a,
+    X: ExceptionHandler<T> + Send + 'a,
     T: Send + 'a,
 {
     fn prepare<B: SequenceBarrier + 'a, D: DataProvider<T> + 'a>(
@@ -74,11 +108,16 @@ where
     fn get_cursor(&self) -> Arc<AtomicSequence> {
         self.cursor.clone()
     }
+
+    fn get_counters(&self) -> Arc<ProcessorCounters> {
+        self.counters.clone()
+    }
 }
 
-impl<'a, E, T> EventProcessorMut<'a, T> for ProcessorMut<E, T>
+impl<'a, E, X, T> EventProcessorMut<'a, T> for ProcessorMut<E, X, T>
 where
     E: EventHandlerMut<T> + Send + 'a,
+    X: ExceptionHandler<T> + Send + 'a,
     T: Send + 'a,
 {
     fn prepare<B: SequenceBarrier + 'a, D: DataProvider<T> + 'a>(
@@ -96,73 +135,232 @@ where
     fn get_cursor(&self) -> Arc<AtomicSequence> {
         self.cursor.clone()
     }
+
+    fn get_counters(&self) -> Arc<ProcessorCounters> {
+        self.counters.clone()
+    }
 }
 
-impl<'a, E, T> EventProcessor<'a, T> for Processor<E, T>
+impl<'a, E, X, T> EventProcessor<'a, T> for Processor<E, X, T>
 where
     E: EventHandler<T> + Send + 'a,
+    X: ExceptionHandler<T> + Send + 'a,
     T: Send + 'a,
 {
 }
 
-impl<E, T, D, B> Runnable for RunnableProcessor<E, T, D, B>
+impl<E, X, T, D, B> RunnableProcessor<E, X, T, D, B>
 where
     E: EventHandler<T> + Send,
+    X: ExceptionHandler<T> + Send,
     D: DataProvider<T>,
     B: SequenceBarrier,
     T: Send,
 {
-    fn run(mut self: Box<Self>) {
+    fn advance(&mut self, blocking: bool) -> StepState {
         let f = &mut self.processor.handler;
+        let x = &mut self.processor.exception_handler;
         let cursor = &self.processor.cursor;
+        let counters = &self.processor.counters;
         let data_provider = &self.data_provider;
         let barrier = &self.barrier;
+        let _guard = HaltOnPanic::new(barrier);
 
-        loop {
-            let next = cursor.get() + 1;
-            let available = match barrier.wait_for(next) {
-                Some(seq) => seq,
-                None => return,
-            };
+        let next = cursor.get() + 1;
+        let available = if blocking {
+            match barrier.wait_for(next) {
+                Ok(seq) => seq,
+                Err(WaitError::Timeout) => {
+                    let sequence = cursor.get();
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| f.on_timeout(sequence)));
+                    if let Err(error) = result {
+                        x.on_timeout_exception(error, sequence);
+                    }
+                    return StepState::Idle;
+                }
+                Err(WaitError::Alerted) => return StepState::Halted,
+            }
+        } else if barrier.is_alerted() {
+            return StepState::Halted;
+        } else {
+            barrier.available()
+        };
 
-            for i in next..=available {
-                let value = unsafe { data_provider.get(i) };
-                f.handle_event(value, i, i == available);
+        if available < next {
+            return StepState::Idle;
+        }
+
+        for i in next..=available {
+            if barrier.is_invalid(i) {
+                continue;
+            }
+            let value = unsafe { data_provider.get(i) };
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                f.handle_event(value, i, i == available)
+            }));
+            if let Err(error) = result {
+                x.handle_event_exception(error, i, value);
             }
+        }
+
+        counters.record_batch((available - next + 1) as u64);
+        cursor.set(available);
+        barrier.signal();
+        StepState::Processed
+    }
+}
+
+impl<E, X, T, D, B> Runnable for RunnableProcessor<E, X, T, D, B>
+where
+    E: EventHandler<T> + Send,
+    X: ExceptionHandler<T> + Send,
+    D: DataProvider<T>,
+    B: SequenceBarrier,
+    T: Send,
+{
+    fn run(mut self: Box<Self>) {
+        self.start();
+        while self.advance(true) != StepState::Halted {}
+        self.shutdown();
+    }
+
+    fn start(&mut self) {
+        let f = &mut self.processor.handler;
+        let x = &mut self.processor.exception_handler;
+        let _guard = HaltOnPanic::new(&self.barrier);
+
+        if let Err(error) = panic::catch_unwind(AssertUnwindSafe(|| f.on_start())) {
+            x.on_start_exception(error);
+        }
+    }
 
-            cursor.set(available);
-            barrier.signal();
+    fn step(&mut self) -> StepState {
+        self.advance(false)
+    }
+
+    fn name(&self) -> String {
+        short_type_name::<E>()
+    }
+
+    fn get_cursor(&self) -> Arc<AtomicSequence> {
+        self.processor.cursor.clone()
+    }
+
+    fn shutdown(&mut self) {
+        let f = &mut self.processor.handler;
+        let x = &mut self.processor.exception_handler;
+        let _guard = HaltOnPanic::new(&self.barrier);
+
+        if let Err(error) = panic::catch_unwind(AssertUnwindSafe(|| f.on_shutdown())) {
+            x.on_shutdown_exception(error);
         }
     }
 }
 
-impl<E, T, D, B> Runnable for RunnableProcessorMut<E, T, D, B>
+impl<E, X, T, D, B> RunnableProcessorMut<E, X, T, D, B>
 where
     E: EventHandlerMut<T> + Send,
+    X: ExceptionHandler<T> + Send,
     D: DataProvider<T>,
     B: SequenceBarrier,
     T: Send,
 {
-    fn run(mut self: Box<Self>) {
+    fn advance(&mut self, blocking: bool) -> StepState {
         let f = &mut self.processor.handler;
+        let x = &mut self.processor.exception_handler;
         let cursor = &self.processor.cursor;
+        let counters = &self.processor.counters;
         let data_provider = &self.data_provider;
         let barrier = &self.barrier;
+        let _guard = HaltOnPanic::new(barrier);
 
-        loop {
-            let next = cursor.get() + 1;
-            let available = match barrier.wait_for(next) {
-                Some(seq) => seq,
-                None => return,
-            };
+        let next = cursor.get() + 1;
+        let available = if blocking {
+            match barrier.wait_for(next) {
+                Ok(seq) => seq,
+                Err(WaitError::Timeout) => {
+                    let sequence = cursor.get();
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| f.on_timeout(sequence)));
+                    if let Err(error) = result {
+                        x.on_timeout_exception(error, sequence);
+                    }
+                    return StepState::Idle;
+                }
+                Err(WaitError::Alerted) => return StepState::Halted,
+            }
+        } else if barrier.is_alerted() {
+            return StepState::Halted;
+        } else {
+            barrier.available()
+        };
 
-            for i in next..=available {
-                let value = unsafe { data_provider.get_mut(i) };
-                f.handle_event(value, i, i == available);
+        if available < next {
+            return StepState::Idle;
+        }
+
+        for i in next..=available {
+            if barrier.is_invalid(i) {
+                continue;
+            }
+            let value = unsafe { data_provider.get_mut(i) };
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                f.handle_event(value, i, i == available)
+            }));
+            if let Err(error) = result {
+                x.handle_event_exception(error, i, value);
             }
+        }
+
+        counters.record_batch((available - next + 1) as u64);
+        cursor.set(available);
+        barrier.signal();
+        StepState::Processed
+    }
+}
+
+impl<E, X, T, D, B> Runnable for RunnableProcessorMut<E, X, T, D, B>
+where
+    E: EventHandlerMut<T> + Send,
+    X: ExceptionHandler<T> + Send,
+    D: DataProvider<T>,
+    B: SequenceBarrier,
+    T: Send,
+{
+    fn run(mut self: Box<Self>) {
+        self.start();
+        while self.advance(true) != StepState::Halted {}
+        self.shutdown();
+    }
+
+    fn start(&mut self) {
+        let f = &mut self.processor.handler;
+        let x = &mut self.processor.exception_handler;
+        let _guard = HaltOnPanic::new(&self.barrier);
+
+        if let Err(error) = panic::catch_unwind(AssertUnwindSafe(|| f.on_start())) {
+            x.on_start_exception(error);
+        }
+    }
+
+    fn step(&mut self) -> StepState {
+        self.advance(false)
+    }
+
+    fn name(&self) -> String {
+        short_type_name::<E>()
+    }
+
+    fn get_cursor(&self) -> Arc<AtomicSequence> {
+        self.processor.cursor.clone()
+    }
+
+    fn shutdown(&mut self) {
+        let f = &mut self.processor.handler;
+        let x = &mut self.processor.exception_handler;
+        let _guard = HaltOnPanic::new(&self.barrier);
 
-            cursor.set(available);
-            barrier.signal();
+        if let Err(error) = panic::catch_unwind(AssertUnwindSafe(|| f.on_shutdown())) {
+            x.on_shutdown_exception(error);
         }
     }
 }
@@ -0,0 +1,232 @@
+use crate::{prelude::*, producer::*, thread::*, utils::*};
+use log::*;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+pub struct AttachedProcessor<B: SequenceBarrier, H: ExecutorHandle> {
+    barrier: B,
+    cursor: Arc<AtomicSequence>,
+    handle: Option<H>,
+    gating_sequences: Arc<GatingSequences>,
+    probes: Arc<Mutex<Vec<HandlerProbe>>>,
+}
+
+impl<B: SequenceBarrier, H: ExecutorHandle> AttachedProcessor<B, H> {
+    pub fn get_cursor(&self) -> Arc<AtomicSequence> {
+        self.cursor.clone()
+    }
+
+    pub fn handle_mut(&mut self) -> Option<&mut H> {
+        self.handle.as_mut()
+    }
+
+    fn release(&mut self) -> Result<(), ExecutorError> {
+        self.barrier.alert();
+        let result = match self.handle.take() {
+            Some(handle) => handle.join(),
+            None => Ok(()),
+        };
+        self.gating_sequences.remove(&self.cursor);
+        self.probes
+            .lock()
+            .unwrap()
+            .retain(|probe| !probe.cursors.iter().any(|c| Arc::ptr_eq(c, &self.cursor)));
+        result
+    }
+}
+
+impl<B: SequenceBarrier, H: ExecutorHandle> Drop for AttachedProcessor<B, H> {
+    fn drop(&mut self) {
+        if let Err(e) = self.release() {
+            error!("attached processor failed: {}", e);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerStats {
+    pub name: String,
+    pub sequence: Sequence,
+    pub lag: i64,
+    pub events: u64,
+    pub batches: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metrics {
+    pub sequencer: SequencerStats,
+    pub handlers: Vec<HandlerStats>,
+}
+
+pub(crate) struct HandlerProbe {
+    name: String,
+    cursors: Vec<Arc<AtomicSequence>>,
+    counters: Vec<Arc<ProcessorCounters>>,
+}
+
+impl HandlerProbe {
+    pub(crate) fn new(
+        name: String,
+        cursors: Vec<Arc<AtomicSequence>>,
+        counters: Vec<Arc<ProcessorCounters>>,
+    ) -> Self {
+        HandlerProbe {
+            name,
+            cursors,
+            counters,
+        }
+    }
+
+    fn stats(&self, cursor: Sequence) -> HandlerStats {
+        let sequence = min_cursor_sequence(&self.cursors);
+        HandlerStats {
+            name: self.name.clone(),
+            sequence,
+            lag: (cursor - sequence).max(0),
+            events: self.counters.iter().map(|c| c.events()).sum(),
+            batches: self.counters.iter().map(|c| c.batches()).sum(),
+        }
+    }
+}
+
+pub struct Disruptor<'a, E: EventProcessorExecutor<'a>, D: DataProvider<T>, T, S: Sequencer> {
+    producer: Producer<D, T, S>,
+    executor: Option<E>,
+    handle: Option<E::Handle>,
+    cursors: Vec<Arc<AtomicSequence>>,
+    probes: Arc<Mutex<Vec<HandlerProbe>>>,
+    next_label: usize,
+    timed_out: bool,
+}
+
+impl<'a, E, D, T, S> Disruptor<'a, E, D, T, S>
+where
+    E: EventProcessorExecutor<'a>,
+    D: DataProvider<T>,
+    S: Sequencer,
+{
+    pub(crate) fn new(
+        executor: E,
+        producer: Producer<D, T, S>,
+        cursors: Vec<Arc<AtomicSequence>>,
+        probes: Vec<HandlerProbe>,
+    ) -> Self {
+        Disruptor {
+            producer,
+            executor: Some(executor),
+            handle: None,
+            cursors,
+            next_label: probes.len(),
+            probes: Arc::new(Mutex::new(probes)),
+            timed_out: false,
+        }
+    }
+
+    pub fn start(&mut self) {
+        if let Some(executor) = self.executor.take() {
+            self.handle = Some(executor.spawn());
+        }
+    }
+
+    pub fn producer(&self) -> &Producer<D, T, S> {
+        &self.producer
+    }
+
+    pub fn handle_mut(&mut self) -> Option<&mut E::Handle> {
+        self.handle.as_mut()
+    }
+
+    pub fn attach<P>(&mut self, processor: P) -> AttachedProcessor<S::Barrier, E::Handle>
+    where
+        P: EventProcessorMut<'a, T>,
+        D: 'a,
+        S::Barrier: 'a,
+    {
+        let sequencer = self.producer.sequencer();
+        let cursor = processor.get_cursor();
+        let counters = processor.get_counters();
+        let barrier = sequencer.create_barrier(&[sequencer.get_cursor()]);
+        sequencer.add_gating_sequence(&cursor);
+
+        let runnable = processor.prepare(barrier.clone(), self.producer.data_provider());
+        let config =
+            ThreadConfig::default().labeled(format!("{}#{}", runnable.name(), self.next_label));
+        self.next_label += 1;
+        self.probes.lock().unwrap().push(HandlerProbe::new(
+            runnable.name(),
+            vec![cursor.clone()],
+            vec![counters],
+        ));
+        let handle = E::with_configured_runnables(vec![(runnable, config)]).spawn();
+        AttachedProcessor {
+            barrier,
+            cursor,
+            handle: Some(handle),
+            gating_sequences: sequencer.gating_sequences(),
+            probes: self.probes.clone(),
+        }
+    }
+
+    pub fn detach(
+        &mut self,
+        mut processor: AttachedProcessor<S::Barrier, E::Handle>,
+    ) -> Result<(), ExecutorError> {
+        processor.release()
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        let sequencer = self.producer.sequencer().stats();
+        let handlers = self
+            .probes
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|probe| probe.stats(sequencer.cursor))
+            .collect();
+        Metrics {
+            sequencer,
+            handlers,
+        }
+    }
+
+    pub fn halt(&mut self) -> Result<(), ExecutorError> {
+        self.producer.sequencer().halt();
+        self.executor = None;
+        match self.handle.take() {
+            Some(handle) => handle.join(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn shutdown(&mut self, timeout: Duration) -> Result<(), ShutdownError> {
+        let deadline = Instant::now() + timeout;
+        let published = self.producer.sequencer().get_cursor().get();
+        while !self.cursors.is_empty() && min_cursor_sequence(&self.cursors) < published {
+            if Instant::now() >= deadline {
+                self.timed_out = true;
+                return Err(TimeoutError.into());
+            }
+            std::thread::yield_now();
+        }
+
+        Ok(self.halt()?)
+    }
+}
+
+impl<'a, E, D, T, S> Drop for Disruptor<'a, E, D, T, S>
+where
+    E: EventProcessorExecutor<'a>,
+    D: DataProvider<T>,
+    S: Sequencer,
+{
+    fn drop(&mut self) {
+        if self.timed_out {
+            self.producer.sequencer().halt();
+            std::mem::forget(self.handle.take());
+        } else if let Err(e) = self.halt() {
+            error!("disruptor halted with failures: {}", e);
+        }
+    }
+}
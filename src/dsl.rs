@@ -1,5 +1,17 @@
-use crate::{consumer::*, executor::*, prelude::*, producer::*, ringbuffer::*, wait::*};
-use std::{marker::PhantomData, sync::Arc};
+use crate::{
+    consumer::*, disruptor::*, executor::*, poller::*, prelude::*, producer::*, ringbuffer::*,
+    thread::*, utils::short_type_name, wait::*, worker::*,
+};
+use std::{
+    fmt,
+    fmt::Write,
+    marker::PhantomData,
+    ops::{Deref, DerefMut, Range},
+    sync::atomic::{AtomicUsize, Ordering},
+    sync::Arc,
+};
+
+static NEXT_PIPELINE: AtomicUsize = AtomicUsize::new(0);
 
 #[derive(Debug)]
 pub struct DisrustorBuilder {}
@@ -17,33 +29,63 @@ where
     T: Send + Sync,
 {
     with_data_provider: WithDataProvider<D, T>,
-    _wait_strategy: PhantomData<W>,
+    wait_strategy: W,
 }
 
 pub struct WithSequencer<S: Sequencer, W: WaitStrategy, D: DataProvider<T>, T>
 where
     T: Send + Sync,
 {
-    with_wait_strategy: WithWaitStrategy<W, D, T>,
+    with_data_provider: WithDataProvider<D, T>,
     sequencer: S,
+    _wait_strategy: PhantomData<W>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HandlerId {
+    pipeline: usize,
+    index: usize,
+}
+
+struct HandlerNode {
+    label: String,
+    cursors: Vec<Arc<AtomicSequence>>,
+    counters: Vec<Arc<ProcessorCounters>>,
+    runnables: Range<usize>,
+    dependencies: Vec<HandlerId>,
+    has_dependents: bool,
+}
+
+struct Topology {
+    pipeline: usize,
+    handlers: Vec<HandlerNode>,
+    barriers: Vec<Vec<HandlerId>>,
+    errors: Vec<BuildError>,
 }
 
 pub struct BarrierScope<'a, S: Sequencer, D: DataProvider<T>, T> {
     sequencer: S,
     data_provider: Arc<D>,
-    gating_sequences: Vec<Arc<AtomicSequence>>,
-    cursors: Vec<Arc<AtomicSequence>>,
-    event_handlers: Vec<Box<dyn Runnable + 'a>>,
+    topology: Topology,
+    dependencies: Vec<HandlerId>,
+    registered: Vec<HandlerId>,
+    event_handlers: Vec<(Box<dyn Runnable + 'a>, ThreadConfig)>,
     _element: PhantomData<T>,
 }
 
+pub struct AfterScope<'s, 'a, S: Sequencer, D: DataProvider<T>, T> {
+    scope: &'s mut BarrierScope<'a, S, D, T>,
+    dependencies: Vec<HandlerId>,
+}
+
 pub struct WithEventHandlers<'a, S: Sequencer, W: WaitStrategy, D: DataProvider<T>, T>
 where
     T: Send + Sync,
 {
     with_sequencer: WithSequencer<S, W, D, T>,
-    event_handlers: Vec<Box<dyn Runnable + 'a>>,
-    gating_sequences: Vec<Arc<AtomicSequence>>,
+    event_handlers: Vec<(Box<dyn Runnable + 'a>, ThreadConfig)>,
+    topology: Topology,
+    dependencies: Vec<HandlerId>,
 }
 
 impl DisrustorBuilder {
@@ -64,25 +106,51 @@ impl DisrustorBuilder {
     {
         Self::new(Arc::new(RingBuffer::new(capacity)))
     }
+
+    pub fn with_ring_buffer_factory<T, F>(
+        capacity: usize,
+        factory: F,
+    ) -> WithDataProvider<RingBuffer<T>, T>
+    where
+        T: Send + Sync,
+        F: FnMut(Sequence) -> T,
+    {
+        Self::new(Arc::new(RingBuffer::with_factory(capacity, factory)))
+    }
 }
 
 impl<D: DataProvider<T>, T> WithDataProvider<D, T>
 where
     T: Send + Sync,
 {
-    pub fn with_wait_strategy<W: WaitStrategy>(self) -> WithWaitStrategy<W, D, T> {
+    pub fn with_wait_strategy<W: WaitStrategy>(
+        self,
+        wait_strategy: W,
+    ) -> WithWaitStrategy<W, D, T> {
         WithWaitStrategy {
             with_data_provider: self,
-            _wait_strategy: Default::default(),
+            wait_strategy,
         }
     }
 
     pub fn with_blocking_wait(self) -> WithWaitStrategy<BlockingWaitStrategy, D, T> {
-        self.with_wait_strategy()
+        self.with_wait_strategy(BlockingWaitStrategy::new())
+    }
+
+    pub fn with_lite_blocking_wait(self) -> WithWaitStrategy<LiteBlockingWaitStrategy, D, T> {
+        self.with_wait_strategy(LiteBlockingWaitStrategy::new())
     }
 
     pub fn with_spin_wait(self) -> WithWaitStrategy<SpinLoopWaitStrategy, D, T> {
-        self.with_wait_strategy()
+        self.with_wait_strategy(SpinLoopWaitStrategy::new())
+    }
+
+    pub fn with_yielding_wait(self) -> WithWaitStrategy<YieldingWaitStrategy, D, T> {
+        self.with_wait_strategy(YieldingWaitStrategy::new())
+    }
+
+    pub fn with_sleeping_wait(self) -> WithWaitStrategy<SleepingWaitStrategy, D, T> {
+        self.with_wait_strategy(SleepingWaitStrategy::new())
     }
 }
 
@@ -92,19 +160,30 @@ where
 {
     pub fn with_sequencer<S: Sequencer>(self, sequencer: S) -> WithSequencer<S, W, D, T> {
         WithSequencer {
-            with_wait_strategy: self,
+            with_data_provider: self.with_data_provider,
             sequencer,
+            _wait_strategy: Default::default(),
         }
     }
 
     pub fn with_single_producer(self) -> WithSequencer<SingleProducerSequencer<W>, W, D, T> {
         let buffer_size = self.with_data_provider.data_provider.buffer_size();
-        self.with_sequencer(SingleProducerSequencer::new(buffer_size, W::new()))
+        let sequencer = SingleProducerSequencer::new(buffer_size, self.wait_strategy);
+        WithSequencer {
+            with_data_provider: self.with_data_provider,
+            sequencer,
+            _wait_strategy: Default::default(),
+        }
     }
 
     pub fn with_multi_producer(self) -> WithSequencer<MultiProducerSequencer<W>, W, D, T> {
         let buffer_size = self.with_data_provider.data_provider.buffer_size();
-        self.with_sequencer(MultiProducerSequencer::new(buffer_size, W::new()))
+        let sequencer = MultiProducerSequencer::new(buffer_size, self.wait_strategy);
+        WithSequencer {
+            with_data_provider: self.with_data_provider,
+            sequencer,
+            _wait_strategy: Default::default(),
+        }
     }
 }
 
@@ -115,67 +194,277 @@ impl<'a, S: Sequencer + 'a, W: WaitStrategy, D: DataProvider<T> + 'a, T: Send +
         mut self,
         f: impl FnOnce(&mut BarrierScope<'a, S, D, T>),
     ) -> WithEventHandlers<'a, S, W, D, T> {
-        let cursor = self.sequencer.get_cursor();
         let mut scope = BarrierScope {
             sequencer: self.sequencer,
-            data_provider: self
-                .with_wait_strategy
-                .with_data_provider
-                .data_provider
-                .clone(),
-            gating_sequences: vec![cursor],
+            data_provider: self.with_data_provider.data_provider.clone(),
+            topology: Topology {
+                pipeline: NEXT_PIPELINE.fetch_add(1, Ordering::Relaxed),
+                handlers: Vec::new(),
+                barriers: Vec::new(),
+                errors: Vec::new(),
+            },
+            dependencies: Vec::new(),
+            registered: Vec::new(),
             event_handlers: Vec::new(),
-            cursors: Vec::new(),
             _element: Default::default(),
         };
 
         f(&mut scope);
         self.sequencer = scope.sequencer;
+        scope.topology.barriers.push(scope.registered.clone());
 
         WithEventHandlers {
             with_sequencer: self,
             event_handlers: scope.event_handlers,
-            gating_sequences: scope.cursors,
+            topology: scope.topology,
+            dependencies: scope.registered,
         }
     }
 }
 
 impl<'a, S: Sequencer + 'a, D: DataProvider<T> + 'a, T: Send + 'a> BarrierScope<'a, S, D, T> {
-    pub fn handle_events<E>(&mut self, handler: E)
+    pub fn handle_events<E>(&mut self, handler: E) -> HandlerId
     where
         E: EventHandler<T> + Send + 'a,
     {
-        self.handle_events_with(BatchEventProcessor::create(handler))
+        let label = short_type_name::<E>();
+        self.handle_events_labeled(BatchEventProcessor::create(handler), label)
     }
 
-    pub fn handle_events_mut<E>(&mut self, handler: E)
+    pub fn handle_events_mut<E>(&mut self, handler: E) -> HandlerId
     where
         E: EventHandlerMut<T> + Send + 'a,
     {
-        self.handle_events_with(BatchEventProcessor::create_mut(handler))
+        let label = short_type_name::<E>();
+        self.handle_events_labeled(BatchEventProcessor::create_mut(handler), label)
     }
 
-    pub fn handle_events_with<E: EventProcessorMut<'a, T>>(&mut self, processor: E) {
-        self.cursors.push(processor.get_cursor());
-        let barrier = self.sequencer.create_barrier(&self.gating_sequences);
+    pub fn handle_events_with_exception_handler<E, X>(
+        &mut self,
+        handler: E,
+        exception_handler: X,
+    ) -> HandlerId
+    where
+        E: EventHandler<T> + Send + 'a,
+        X: ExceptionHandler<T> + Send + 'a,
+    {
+        let label = short_type_name::<E>();
+        self.handle_events_labeled(
+            BatchEventProcessor::create_with_exception_handler(handler, exception_handler),
+            label,
+        )
+    }
 
-        let runnable = processor.prepare(barrier, self.data_provider.clone());
-        self.event_handlers.push(runnable);
+    pub fn handle_events_mut_with_exception_handler<E, X>(
+        &mut self,
+        handler: E,
+        exception_handler: X,
+    ) -> HandlerId
+    where
+        E: EventHandlerMut<T> + Send + 'a,
+        X: ExceptionHandler<T> + Send + 'a,
+    {
+        let label = short_type_name::<E>();
+        self.handle_events_labeled(
+            BatchEventProcessor::create_mut_with_exception_handler(handler, exception_handler),
+            label,
+        )
+    }
+
+    pub fn handle_events_with_worker_pool<E, I>(&mut self, handlers: I) -> HandlerId
+    where
+        E: WorkHandler<T> + Send + 'a,
+        I: IntoIterator<Item = E>,
+    {
+        let (gating_sequences, dependencies) = self.gating_sequences();
+        let start = self.event_handlers.len();
+        let processors: Vec<_> = WorkerPool::create(handlers)
+            .into_iter()
+            .map(|processor| self.add_processor(&gating_sequences, processor))
+            .collect();
+        let label = format!("{} x{}", short_type_name::<E>(), processors.len());
+        let empty = processors.is_empty();
+        let id = self.register(label, processors, dependencies, start);
+        if empty {
+            self.topology.errors.push(BuildError::EmptyWorkerPool(id));
+        }
+        id
+    }
+
+    pub fn create_event_poller(&mut self) -> EventPoller<S::Barrier, D, T> {
+        let (gating_sequences, dependencies) = self.gating_sequences();
+        let barrier = self.sequencer.create_barrier(&gating_sequences);
+        let poller = EventPoller::new(
+            barrier,
+            self.data_provider.clone(),
+            self.sequencer.get_cursor(),
+        );
+        let start = self.event_handlers.len();
+        self.register(
+            "EventPoller".to_string(),
+            vec![(poller.get_cursor(), poller.get_counters())],
+            dependencies,
+            start,
+        );
+        poller
+    }
+
+    pub fn handle_events_with<E: EventProcessorMut<'a, T>>(&mut self, processor: E) -> HandlerId {
+        let label = short_type_name::<E>();
+        self.handle_events_labeled(processor, label)
+    }
+
+    pub fn after(&mut self, handlers: &[HandlerId]) -> AfterScope<'_, 'a, S, D, T> {
+        let dependencies = std::mem::replace(&mut self.dependencies, handlers.to_vec());
+        AfterScope {
+            scope: self,
+            dependencies,
+        }
     }
 
     pub fn with_barrier(mut self, f: impl FnOnce(&mut BarrierScope<'a, S, D, T>)) {
         let mut scope = BarrierScope {
             sequencer: self.sequencer,
             data_provider: self.data_provider.clone(),
-            gating_sequences: self.cursors,
+            topology: self.topology,
+            dependencies: self.registered,
+            registered: Vec::new(),
             event_handlers: Vec::new(),
-            cursors: Vec::new(),
             _element: Default::default(),
         };
 
         f(&mut scope);
         self.event_handlers.append(&mut scope.event_handlers);
     }
+
+    pub fn configure(&mut self, id: HandlerId, config: ThreadConfig) {
+        let topology = &mut self.topology;
+        let node = match topology.handlers.get(id.index) {
+            Some(node) if id.pipeline == topology.pipeline => node,
+            _ => {
+                topology.errors.push(BuildError::UnknownHandler(id));
+                return;
+            }
+        };
+
+        let runnables = node.runnables.clone();
+        let pooled = runnables.len() > 1;
+        for (index, slot) in runnables.enumerate() {
+            self.event_handlers[slot].1 = if pooled {
+                config.indexed(index)
+            } else {
+                config.clone()
+            };
+        }
+    }
+
+    fn handle_events_labeled<E: EventProcessorMut<'a, T>>(
+        &mut self,
+        processor: E,
+        label: String,
+    ) -> HandlerId {
+        let (gating_sequences, dependencies) = self.gating_sequences();
+        let start = self.event_handlers.len();
+        let processor = self.add_processor(&gating_sequences, processor);
+        self.register(label, vec![processor], dependencies, start)
+    }
+
+    fn gating_sequences(&mut self) -> (Vec<Arc<AtomicSequence>>, Vec<HandlerId>) {
+        let topology = &mut self.topology;
+        let mut gating_sequences = Vec::new();
+        let mut dependencies = Vec::new();
+        for &id in &self.dependencies {
+            let node = match topology.handlers.get_mut(id.index) {
+                Some(node) if id.pipeline == topology.pipeline => node,
+                _ => {
+                    topology.errors.push(BuildError::UnknownHandler(id));
+                    continue;
+                }
+            };
+            node.has_dependents = true;
+            gating_sequences.extend(node.cursors.iter().cloned());
+            dependencies.push(id);
+        }
+
+        if gating_sequences.is_empty() {
+            gating_sequences.push(self.sequencer.get_cursor());
+        }
+        (gating_sequences, dependencies)
+    }
+
+    fn add_processor<E: EventProcessorMut<'a, T>>(
+        &mut self,
+        gating_sequences: &[Arc<AtomicSequence>],
+        processor: E,
+    ) -> (Arc<AtomicSequence>, Arc<ProcessorCounters>) {
+        let cursor = processor.get_cursor();
+        let counters = processor.get_counters();
+        let barrier = self.sequencer.create_barrier(gating_sequences);
+        let runnable = processor.prepare(barrier, self.data_provider.clone());
+        self.event_handlers
+            .push((runnable, ThreadConfig::default()));
+        (cursor, counters)
+    }
+
+    fn register(
+        &mut self,
+        label: String,
+        processors: Vec<(Arc<AtomicSequence>, Arc<ProcessorCounters>)>,
+        dependencies: Vec<HandlerId>,
+        start: usize,
+    ) -> HandlerId {
+        let (cursors, counters): (Vec<_>, Vec<_>) = processors.into_iter().unzip();
+        let topology = &mut self.topology;
+        let id = HandlerId {
+            pipeline: topology.pipeline,
+            index: topology.handlers.len(),
+        };
+        for (index, node) in topology.handlers.iter().enumerate() {
+            if node
+                .cursors
+                .iter()
+                .any(|c| cursors.iter().any(|cursor| Arc::ptr_eq(c, cursor)))
+            {
+                topology
+                    .errors
+                    .push(BuildError::DuplicateHandler(HandlerId {
+                        pipeline: topology.pipeline,
+                        index,
+                    }));
+            }
+        }
+
+        topology.handlers.push(HandlerNode {
+            label,
+            cursors,
+            counters,
+            runnables: start..self.event_handlers.len(),
+            dependencies,
+            has_dependents: false,
+        });
+        self.registered.push(id);
+        id
+    }
+}
+
+impl<'s, 'a, S: Sequencer, D: DataProvider<T>, T> Deref for AfterScope<'s, 'a, S, D, T> {
+    type Target = BarrierScope<'a, S, D, T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.scope
+    }
+}
+
+impl<'s, 'a, S: Sequencer, D: DataProvider<T>, T> DerefMut for AfterScope<'s, 'a, S, D, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.scope
+    }
+}
+
+impl<'s, 'a, S: Sequencer, D: DataProvider<T>, T> Drop for AfterScope<'s, 'a, S, D, T> {
+    fn drop(&mut self) {
+        std::mem::swap(&mut self.scope.dependencies, &mut self.dependencies);
+    }
 }
 
 impl<'a, S: Sequencer + 'a, W: WaitStrategy, D: DataProvider<T> + 'a, T: Send + Sync + 'a>
@@ -183,51 +472,231 @@ impl<'a, S: Sequencer + 'a, W: WaitStrategy, D: DataProvider<T> + 'a, T: Send +
 {
     pub fn with_barrier(mut self, f: impl FnOnce(&mut BarrierScope<'a, S, D, T>)) -> Self {
         let mut scope = BarrierScope {
-            gating_sequences: self.gating_sequences.clone(),
-            cursors: Vec::new(),
             sequencer: self.with_sequencer.sequencer,
-            data_provider: self
-                .with_sequencer
-                .with_wait_strategy
-                .with_data_provider
-                .data_provider
-                .clone(),
-            event_handlers: Vec::new(),
+            data_provider: self.with_sequencer.with_data_provider.data_provider.clone(),
+            topology: self.topology,
+            dependencies: self.dependencies,
+            registered: Vec::new(),
+            event_handlers: self.event_handlers,
             _element: Default::default(),
         };
 
         f(&mut scope);
         self.with_sequencer.sequencer = scope.sequencer;
-        self.event_handlers.append(&mut scope.event_handlers);
-        self.gating_sequences = scope.cursors;
+        self.event_handlers = scope.event_handlers;
+        scope.topology.barriers.push(scope.registered.clone());
+        self.topology = scope.topology;
+        self.dependencies = scope.registered;
 
         self
     }
 
-    pub fn build(
-        self,
-    ) -> (
-        impl EventProcessorExecutor<'a>,
-        impl EventProducer<'a, Item = T>,
-    ) {
-        self.build_with_executor::<ThreadedExecutor<'a>>()
+    pub fn validate(self) -> Result<Self, Vec<BuildError>> {
+        let errors = self.errors();
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph disrustor {\n    producer [shape=box];\n");
+        for (index, node) in self.topology.handlers.iter().enumerate() {
+            writeln!(dot, "    h{} [label={:?}];", index, node.label).unwrap();
+        }
+        for (index, node) in self.topology.handlers.iter().enumerate() {
+            if node.dependencies.is_empty() {
+                writeln!(dot, "    producer -> h{};", index).unwrap();
+            }
+            for dependency in &node.dependencies {
+                writeln!(dot, "    h{} -> h{};", dependency.index, index).unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
     }
 
     pub fn build_with_executor<E: EventProcessorExecutor<'a>>(
-        mut self,
-    ) -> (E, impl EventProducer<'a, Item = T>) {
-        for gs in &self.gating_sequences {
+        self,
+    ) -> Result<(E, Producer<D, T, S>), BuildError> {
+        let Parts {
+            runnables,
+            producer,
+            ..
+        } = self.into_parts()?;
+        Ok((E::with_configured_runnables(runnables), producer))
+    }
+
+    pub fn build_disruptor_with_executor<E: EventProcessorExecutor<'a>>(
+        self,
+    ) -> Result<Disruptor<'a, E, D, T, S>, BuildError> {
+        let Parts {
+            runnables,
+            producer,
+            cursors,
+            probes,
+        } = self.into_parts()?;
+        let executor = E::with_configured_runnables(runnables);
+        Ok(Disruptor::new(executor, producer, cursors, probes))
+    }
+
+    pub fn run_scoped<F, R>(self, f: F) -> Result<Result<R, ExecutorError>, BuildError>
+    where
+        F: FnOnce(&Producer<D, T, S>) -> R,
+    {
+        let Parts {
+            runnables,
+            producer,
+            ..
+        } = self.into_parts()?;
+        let executor = ScopedExecutor::with_configured_runnables(runnables);
+        Ok(std::thread::scope(|scope| {
+            let handle = executor.spawn(scope);
+            let result = f(&producer);
+            producer.drain();
+            handle.join().map(|_| result)
+        }))
+    }
+
+    fn errors(&self) -> Vec<BuildError> {
+        let topology = &self.topology;
+        let mut errors = topology.errors.clone();
+        for (barrier, handlers) in topology.barriers.iter().enumerate() {
+            if handlers.is_empty() {
+                errors.push(BuildError::EmptyBarrier(barrier));
+            }
+        }
+
+        // Leaves gate the producer directly, so a handler is only left
+        // unobserved when every handler after it has no cursor of its own.
+        for (index, node) in topology.handlers.iter().enumerate() {
+            let gated = !node.has_dependents
+                || topology.handlers.iter().any(|dependent| {
+                    !dependent.cursors.is_empty()
+                        && dependent.dependencies.iter().any(|id| id.index == index)
+                });
+            if !node.cursors.is_empty() && !gated {
+                errors.push(BuildError::Ungated(HandlerId {
+                    pipeline: topology.pipeline,
+                    index,
+                }));
+            }
+        }
+        errors
+    }
+
+    fn into_parts(self) -> Result<Parts<'a, D, T, S>, BuildError> {
+        if let Some(error) = self.errors().into_iter().next() {
+            return Err(error);
+        }
+
+        let gating_sequences: Vec<_> = self
+            .topology
+            .handlers
+            .iter()
+            .filter(|node| !node.has_dependents)
+            .flat_map(|node| node.cursors.iter().cloned())
+            .collect();
+        for gs in &gating_sequences {
             self.with_sequencer.sequencer.add_gating_sequence(gs);
         }
-        let executor = E::with_runnables(self.event_handlers);
+        let probes = self
+            .topology
+            .handlers
+            .iter()
+            .map(|node| {
+                HandlerProbe::new(
+                    node.label.clone(),
+                    node.cursors.clone(),
+                    node.counters.clone(),
+                )
+            })
+            .collect();
+        let mut runnables = self.event_handlers;
+        for (index, node) in self.topology.handlers.iter().enumerate() {
+            let pooled = node.runnables.len() > 1;
+            for (worker, slot) in node.runnables.clone().enumerate() {
+                let label = if pooled {
+                    format!("{}#{}-{}", node.label, index, worker)
+                } else {
+                    format!("{}#{}", node.label, index)
+                };
+                let config = std::mem::take(&mut runnables[slot].1);
+                runnables[slot].1 = config.labeled(label);
+            }
+        }
         let producer = Producer::new(
-            self.with_sequencer
-                .with_wait_strategy
-                .with_data_provider
-                .data_provider
-                .clone(),
+            self.with_sequencer.with_data_provider.data_provider.clone(),
             self.with_sequencer.sequencer,
         );
-        (executor, producer)
+        Ok(Parts {
+            runnables,
+            producer,
+            cursors: gating_sequences,
+            probes,
+        })
+    }
+}
+
+impl<
+        S: Sequencer + 'static,
+        W: WaitStrategy,
+        D: DataProvider<T> + 'static,
+        T: Send + Sync + 'static,
+    > WithEventHandlers<'static, S, W, D, T>
+{
+    pub fn build(
+        self,
+    ) -> Result<(impl EventProcessorExecutor<'static>, Producer<D, T, S>), BuildError> {
+        self.build_with_executor::<ThreadedExecutor>()
     }
+
+    pub fn build_disruptor(
+        self,
+    ) -> Result<Disruptor<'static, ThreadedExecutor, D, T, S>, BuildError> {
+        self.build_disruptor_with_executor()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    EmptyBarrier(usize),
+    DuplicateHandler(HandlerId),
+    UnknownHandler(HandlerId),
+    EmptyWorkerPool(HandlerId),
+    Ungated(HandlerId),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::EmptyBarrier(barrier) => {
+                write!(f, "barrier {} has no event handlers", barrier)
+            }
+            BuildError::DuplicateHandler(id) => {
+                write!(f, "handler {} is registered more than once", id.index)
+            }
+            BuildError::UnknownHandler(id) => {
+                write!(f, "handler {} does not belong to this pipeline", id.index)
+            }
+            BuildError::EmptyWorkerPool(id) => {
+                write!(f, "worker pool {} has no workers", id.index)
+            }
+            BuildError::Ungated(id) => {
+                write!(f, "nothing gates on handler {}", id.index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+type ConfiguredRunnables<'a> = Vec<(Box<dyn Runnable + 'a>, ThreadConfig)>;
+
+struct Parts<'a, D: DataProvider<T>, T, S: Sequencer> {
+    runnables: ConfiguredRunnables<'a>,
+    producer: Producer<D, T, S>,
+    cursors: Vec<Arc<AtomicSequence>>,
+    probes: Vec<HandlerProbe>,
 }
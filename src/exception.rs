@@ -0,0 +1,75 @@
+use crate::{prelude::*, utils::*};
+use log::*;
+use std::any::Any;
+
+pub struct FatalExceptionHandler;
+
+pub struct IgnoreExceptionHandler;
+
+impl<T> ExceptionHandler<T> for FatalExceptionHandler {
+    fn handle_event_exception(&mut self, error: Box<dyn Any + Send>, sequence: Sequence, _: &T) {
+        error!(
+            "exception processing sequence {}: {}",
+            sequence,
+            panic_message(error.as_ref())
+        );
+        std::panic::resume_unwind(error);
+    }
+
+    fn on_timeout_exception(&mut self, error: Box<dyn Any + Send>, sequence: Sequence) {
+        error!(
+            "exception during on_timeout at sequence {}: {}",
+            sequence,
+            panic_message(error.as_ref())
+        );
+        std::panic::resume_unwind(error);
+    }
+
+    fn on_start_exception(&mut self, error: Box<dyn Any + Send>) {
+        error!(
+            "exception during on_start: {}",
+            panic_message(error.as_ref())
+        );
+        std::panic::resume_unwind(error);
+    }
+
+    fn on_shutdown_exception(&mut self, error: Box<dyn Any + Send>) {
+        error!(
+            "exception during on_shutdown: {}",
+            panic_message(error.as_ref())
+        );
+        std::panic::resume_unwind(error);
+    }
+}
+
+impl<T> ExceptionHandler<T> for IgnoreExceptionHandler {
+    fn handle_event_exception(&mut self, error: Box<dyn Any + Send>, sequence: Sequence, _: &T) {
+        warn!(
+            "ignoring exception processing sequence {}: {}",
+            sequence,
+            panic_message(error.as_ref())
+        );
+    }
+
+    fn on_timeout_exception(&mut self, error: Box<dyn Any + Send>, sequence: Sequence) {
+        warn!(
+            "ignoring exception during on_timeout at sequence {}: {}",
+            sequence,
+            panic_message(error.as_ref())
+        );
+    }
+
+    fn on_start_exception(&mut self, error: Box<dyn Any + Send>) {
+        warn!(
+            "ignoring exception during on_start: {}",
+            panic_message(error.as_ref())
+        );
+    }
+
+    fn on_shutdown_exception(&mut self, error: Box<dyn Any + Send>) {
+        warn!(
+            "ignoring exception during on_shutdown: {}",
+            panic_message(error.as_ref())
+        );
+    }
+}
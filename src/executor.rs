@@ -1,44 +1,288 @@
-use crate::prelude::*;
+use crate::{prelude::*, thread::*};
+use log::*;
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+};
 
-pub struct ThreadedExecutor<'a> {
-    runnables: Vec<Box<dyn Runnable + 'a>>,
+pub struct ThreadedExecutor {
+    runnables: Vec<(Box<dyn Runnable>, ThreadConfig)>,
+}
+
+struct SpawnedProcessor<H> {
+    handle: H,
+    name: String,
+    cursor: Arc<AtomicSequence>,
+}
+
+impl<H> SpawnedProcessor<H> {
+    fn new(handle: H, name: String, cursor: Arc<AtomicSequence>) -> Self {
+        SpawnedProcessor {
+            handle,
+            name,
+            cursor,
+        }
+    }
+}
+
+fn processor_failure(
+    name: String,
+    cursor: &AtomicSequence,
+    result: std::thread::Result<()>,
+) -> Option<ProcessorFailure> {
+    result
+        .err()
+        .map(|payload| ProcessorFailure::new(name, payload, cursor.get()))
+}
+
+fn collect_failures(failures: Vec<ProcessorFailure>) -> Result<(), ExecutorError> {
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(ExecutorError::new(failures))
+    }
 }
 
 pub struct ThreadedExecutorHandle {
-    threads: Vec<std::thread::JoinHandle<()>>,
+    threads: Vec<SpawnedProcessor<std::thread::JoinHandle<()>>>,
 }
 
-impl<'a> EventProcessorExecutor<'a> for ThreadedExecutor<'a> {
+impl EventProcessorExecutor<'static> for ThreadedExecutor {
     type Handle = ThreadedExecutorHandle;
 
-    fn with_runnables(runnables: Vec<Box<dyn Runnable + 'a>>) -> Self {
+    fn with_runnables(runnables: Vec<Box<dyn Runnable>>) -> Self {
+        Self::with_configured_runnables(
+            runnables
+                .into_iter()
+                .map(|r| (r, ThreadConfig::default()))
+                .collect(),
+        )
+    }
+
+    fn with_configured_runnables(runnables: Vec<(Box<dyn Runnable>, ThreadConfig)>) -> Self {
         Self { runnables }
     }
 
     fn spawn(self) -> Self::Handle {
         let mut threads = Vec::new();
-        for r in self.runnables.into_iter() {
-            let b = unsafe {
-                std::mem::transmute::<Box<dyn Runnable + 'a>, Box<dyn Runnable + 'static>>(r)
-            };
-            threads.push(std::thread::spawn(move || b.run()));
+        for (r, config) in self.runnables.into_iter() {
+            let name = config.thread_name(r.as_ref());
+            let label = name.clone();
+            let cursor = r.get_cursor();
+            let thread = config
+                .builder()
+                .spawn(move || {
+                    config.apply(&label);
+                    r.run()
+                })
+                .expect("failed to spawn thread");
+            threads.push(SpawnedProcessor::new(thread, name, cursor));
         }
 
         ThreadedExecutorHandle { threads }
     }
 }
 
+impl ThreadedExecutorHandle {
+    fn join_threads(&mut self) -> Result<(), ExecutorError> {
+        let failures = std::mem::take(&mut self.threads)
+            .into_iter()
+            .filter_map(|t| processor_failure(t.name, &t.cursor, t.handle.join()))
+            .collect();
+        collect_failures(failures)
+    }
+}
+
 impl ExecutorHandle for ThreadedExecutorHandle {
-    fn join(self) {
-        drop(self)
+    fn join(mut self) -> Result<(), ExecutorError> {
+        self.join_threads()
     }
 }
 
 impl Drop for ThreadedExecutorHandle {
     fn drop(&mut self) {
-        let threads = std::mem::take(&mut self.threads);
-        for t in threads.into_iter() {
-            t.join().unwrap();
+        if let Err(e) = self.join_threads() {
+            error!("executor dropped with failures: {}", e);
+        }
+    }
+}
+
+pub struct ScopedExecutor<'a> {
+    runnables: Vec<(Box<dyn Runnable + 'a>, ThreadConfig)>,
+}
+
+pub struct ScopedExecutorHandle<'scope> {
+    threads: Vec<SpawnedProcessor<std::thread::ScopedJoinHandle<'scope, ()>>>,
+}
+
+impl<'a> ScopedExecutor<'a> {
+    pub fn with_runnables(runnables: Vec<Box<dyn Runnable + 'a>>) -> Self {
+        Self::with_configured_runnables(
+            runnables
+                .into_iter()
+                .map(|r| (r, ThreadConfig::default()))
+                .collect(),
+        )
+    }
+
+    pub fn with_configured_runnables(
+        runnables: Vec<(Box<dyn Runnable + 'a>, ThreadConfig)>,
+    ) -> Self {
+        Self { runnables }
+    }
+
+    pub fn spawn<'scope, 'env>(
+        self,
+        scope: &'scope std::thread::Scope<'scope, 'env>,
+    ) -> ScopedExecutorHandle<'scope>
+    where
+        'a: 'scope,
+    {
+        let mut threads = Vec::new();
+        for (r, config) in self.runnables.into_iter() {
+            let name = config.thread_name(r.as_ref());
+            let label = name.clone();
+            let cursor = r.get_cursor();
+            let thread = config
+                .builder()
+                .spawn_scoped(scope, move || {
+                    config.apply(&label);
+                    r.run()
+                })
+                .expect("failed to spawn thread");
+            threads.push(SpawnedProcessor::new(thread, name, cursor));
+        }
+
+        ScopedExecutorHandle { threads }
+    }
+}
+
+impl<'scope> ExecutorHandle for ScopedExecutorHandle<'scope> {
+    fn join(self) -> Result<(), ExecutorError> {
+        let failures = self
+            .threads
+            .into_iter()
+            .filter_map(|t| processor_failure(t.name, &t.cursor, t.handle.join()))
+            .collect();
+        collect_failures(failures)
+    }
+}
+
+pub struct CooperativeExecutor<'a> {
+    runnables: Vec<(Box<dyn Runnable + 'a>, String)>,
+}
+
+struct CooperativeProcessor<'a> {
+    runnable: Box<dyn Runnable + 'a>,
+    name: String,
+    halted: bool,
+    failure: Option<ProcessorFailure>,
+}
+
+impl<'a> CooperativeProcessor<'a> {
+    fn guard<F: FnOnce(&mut dyn Runnable) -> StepState>(&mut self, f: F) -> StepState {
+        let runnable = &mut self.runnable;
+        match panic::catch_unwind(AssertUnwindSafe(|| f(runnable.as_mut()))) {
+            Ok(state) => {
+                self.halted |= state == StepState::Halted;
+                state
+            }
+            Err(payload) => {
+                let cursor = self.runnable.get_cursor();
+                self.failure = processor_failure(self.name.clone(), &cursor, Err(payload));
+                self.halted = true;
+                StepState::Halted
+            }
+        }
+    }
+}
+
+pub struct CooperativeExecutorHandle<'a> {
+    processors: Vec<CooperativeProcessor<'a>>,
+}
+
+impl<'a> EventProcessorExecutor<'a> for CooperativeExecutor<'a> {
+    type Handle = CooperativeExecutorHandle<'a>;
+
+    fn with_runnables(runnables: Vec<Box<dyn Runnable + 'a>>) -> Self {
+        Self::with_configured_runnables(
+            runnables
+                .into_iter()
+                .map(|r| (r, ThreadConfig::default()))
+                .collect(),
+        )
+    }
+
+    fn with_configured_runnables(runnables: Vec<(Box<dyn Runnable + 'a>, ThreadConfig)>) -> Self {
+        let runnables = runnables
+            .into_iter()
+            .map(|(r, config)| {
+                let name = config.thread_name(r.as_ref());
+                (r, name)
+            })
+            .collect();
+        Self { runnables }
+    }
+
+    fn spawn(self) -> Self::Handle {
+        let mut processors: Vec<_> = self
+            .runnables
+            .into_iter()
+            .map(|(runnable, name)| CooperativeProcessor {
+                runnable,
+                name,
+                halted: false,
+                failure: None,
+            })
+            .collect();
+        for p in processors.iter_mut() {
+            p.guard(|r| {
+                r.start();
+                StepState::Idle
+            });
+        }
+
+        CooperativeExecutorHandle { processors }
+    }
+}
+
+impl<'a> CooperativeExecutorHandle<'a> {
+    pub fn step(&mut self) -> StepState {
+        let mut state = StepState::Halted;
+        for p in self.processors.iter_mut().filter(|p| !p.halted) {
+            match p.guard(|r| r.step()) {
+                StepState::Processed => state = StepState::Processed,
+                StepState::Idle if state == StepState::Halted => state = StepState::Idle,
+                StepState::Idle | StepState::Halted => {}
+            }
+        }
+        state
+    }
+
+    pub fn run_until_idle(&mut self) -> StepState {
+        loop {
+            match self.step() {
+                StepState::Processed => continue,
+                state => return state,
+            }
+        }
+    }
+}
+
+impl<'a> ExecutorHandle for CooperativeExecutorHandle<'a> {
+    fn join(mut self) -> Result<(), ExecutorError> {
+        self.run_until_idle();
+        for p in self.processors.iter_mut().filter(|p| p.failure.is_none()) {
+            p.guard(|r| {
+                r.shutdown();
+                StepState::Halted
+            });
         }
+        let failures = self
+            .processors
+            .into_iter()
+            .filter_map(|p| p.failure)
+            .collect();
+        collect_failures(failures)
     }
 }
@@ -1,29 +1,49 @@
 mod barrier;
 mod consumer;
+mod disruptor;
 mod dsl;
+mod exception;
 mod executor;
+mod poller;
 mod prelude;
 mod producer;
 mod ringbuffer;
+#[cfg(feature = "stream")]
+mod stream;
+mod thread;
 mod utils;
 mod wait;
+mod worker;
 
+pub use disruptor::*;
 pub use dsl::*;
 pub use prelude::*;
+pub use thread::*;
 pub mod internal {
     pub use super::barrier::*;
     pub use super::consumer::*;
+    pub use super::exception::*;
     pub use super::executor::*;
+    pub use super::poller::*;
     pub use super::producer::*;
     pub use super::ringbuffer::*;
+    #[cfg(feature = "stream")]
+    pub use super::stream::*;
     pub use super::wait::*;
+    pub use super::worker::*;
 }
 
 #[cfg(test)]
 mod test {
     use super::internal::*;
     use super::*;
-    use std::sync::Arc;
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicI64, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
 
     struct Checker;
     impl EventHandler<i64> for Checker {
@@ -44,11 +64,128 @@ mod test {
             }
         }
     }
+
+    struct Counter {
+        count: Arc<AtomicI64>,
+        sum: Arc<AtomicI64>,
+    }
+    impl WorkHandler<i64> for Counter {
+        fn handle_event(&mut self, data: &i64, sequence: Sequence) {
+            assert_eq!(*data, sequence);
+            self.count.fetch_add(1, Ordering::Relaxed);
+            self.sum.fetch_add(*data, Ordering::Relaxed);
+        }
+    }
+
+    struct IdleDetector {
+        last_timeout: Arc<AtomicI64>,
+    }
+    impl EventHandler<i64> for IdleDetector {
+        fn handle_event(&mut self, _: &i64, _: Sequence, _: bool) {}
+
+        fn on_timeout(&mut self, sequence: Sequence) {
+            self.last_timeout.store(sequence, Ordering::Relaxed);
+        }
+    }
+
+    struct Faulty {
+        count: Arc<AtomicI64>,
+    }
+    impl EventHandler<i64> for Faulty {
+        fn handle_event(&mut self, _: &i64, sequence: Sequence, _: bool) {
+            if sequence == 5 {
+                panic!("faulty event");
+            }
+            self.count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    struct Lifecycle {
+        thread: Option<std::thread::ThreadId>,
+        shutdowns: Arc<AtomicI64>,
+    }
+    impl EventHandler<i64> for Lifecycle {
+        fn handle_event(&mut self, _: &i64, _: Sequence, _: bool) {
+            assert_eq!(self.thread, Some(std::thread::current().id()));
+        }
+
+        fn on_start(&mut self) {
+            self.thread = Some(std::thread::current().id());
+        }
+
+        fn on_shutdown(&mut self) {
+            assert_eq!(self.thread, Some(std::thread::current().id()));
+            self.shutdowns.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    struct Tap {
+        count: Arc<AtomicI64>,
+    }
+    impl EventHandler<i64> for Tap {
+        fn handle_event(&mut self, data: &i64, sequence: Sequence, _: bool) {
+            assert_eq!(*data, sequence);
+            self.count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    struct Stage {
+        last: Arc<AtomicI64>,
+        upstream: Vec<Arc<AtomicI64>>,
+    }
+    impl EventHandler<i64> for Stage {
+        fn handle_event(&mut self, data: &i64, sequence: Sequence, _: bool) {
+            assert_eq!(*data, sequence);
+            for upstream in &self.upstream {
+                assert!(upstream.load(Ordering::Acquire) >= sequence);
+            }
+            self.last.store(sequence, Ordering::Release);
+        }
+    }
+
+    struct Gate {
+        open: Arc<AtomicBool>,
+    }
+    impl EventHandler<i64> for Gate {
+        fn handle_event(&mut self, _: &i64, _: Sequence, _: bool) {
+            while !self.open.load(Ordering::Acquire) {
+                std::thread::yield_now();
+            }
+        }
+    }
+
+    struct Offset;
+    impl EventTranslatorTwoArg<i64, i64, i64> for Offset {
+        fn translate_to(&self, event: &mut i64, _: Sequence, base: i64, offset: i64) {
+            *event = base + offset;
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        struct ThreadWaker(std::thread::Thread);
+        impl std::task::Wake for ThreadWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let waker = std::task::Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = std::task::Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                std::task::Poll::Ready(output) => return output,
+                std::task::Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
     #[test]
     fn test_blocking_wait_strategy() {
         let ring_buffer: Arc<RingBuffer<i64>> = Arc::new(RingBuffer::new(4096));
         let wait_strategy = BlockingWaitStrategy::new();
-        let mut sequencer = SingleProducerSequencer::new(ring_buffer.buffer_size(), wait_strategy);
+        let sequencer = SingleProducerSequencer::new(ring_buffer.buffer_size(), wait_strategy);
 
         let gating_sequences = vec![sequencer.get_cursor()];
         let barrier = sequencer.create_barrier(&gating_sequences);
@@ -64,13 +201,15 @@ mod test {
 
         for _ in 0..10_000 {
             let buffer: Vec<_> = std::iter::repeat(1).take(1000).collect();
-            producer.write(buffer, |slot, seq, _| {
-                *slot = seq;
-            });
+            producer
+                .write(buffer, |slot, seq, _| {
+                    *slot = seq;
+                })
+                .unwrap();
         }
 
         producer.drain();
-        handle.join();
+        handle.join().unwrap();
     }
 
     #[test]
@@ -82,16 +221,1431 @@ mod test {
             .with_barrier(|b| {
                 b.handle_events_mut(Checker {});
             })
-            .build();
+            .build()
+            .unwrap();
 
         let handle = executor.spawn();
         for _ in 0..10_000 {
             let buffer: Vec<_> = std::iter::repeat(1).take(1000).collect();
-            producer.write(buffer, |slot, seq, _| {
+            producer
+                .write(buffer, |slot, seq, _| {
+                    *slot = seq;
+                })
+                .unwrap();
+        }
+        producer.drain();
+        handle.join().unwrap();
+    }
+
+    fn run_pipeline<W: WaitStrategy + 'static>(wait_strategy: W) {
+        let ring_buffer: Arc<RingBuffer<i64>> = Arc::new(RingBuffer::new(4096));
+        let (executor, producer) = dsl::DisrustorBuilder::new(ring_buffer)
+            .with_wait_strategy(wait_strategy)
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events_mut(Checker {});
+            })
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        for _ in 0..1_000 {
+            let buffer: Vec<_> = std::iter::repeat(1).take(1000).collect();
+            producer
+                .write(buffer, |slot, seq, _| {
+                    *slot = seq;
+                })
+                .unwrap();
+        }
+        producer.drain();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_lite_blocking_wait_strategy() {
+        run_pipeline(LiteBlockingWaitStrategy::new());
+    }
+
+    #[test]
+    fn test_yielding_wait_strategy() {
+        run_pipeline(YieldingWaitStrategy::new());
+    }
+
+    #[test]
+    fn test_sleeping_wait_strategy() {
+        run_pipeline(SleepingWaitStrategy::new());
+    }
+
+    #[test]
+    fn test_timeout_blocking_wait_strategy() {
+        let last_timeout = Arc::new(AtomicI64::new(Sequence::MIN));
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_wait_strategy(TimeoutBlockingWaitStrategy::with_timeout(
+                Duration::from_millis(1),
+            ))
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(IdleDetector {
+                    last_timeout: last_timeout.clone(),
+                });
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        producer
+            .write(0..10, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        while last_timeout.load(Ordering::Relaxed) != 9 {
+            assert!(std::time::Instant::now() < deadline);
+            std::thread::yield_now();
+        }
+
+        producer.drain();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_phased_backoff_wait_strategy() {
+        run_pipeline(PhasedBackoffWaitStrategy::with_lite_lock(
+            Duration::from_micros(10),
+            Duration::from_micros(50),
+        ));
+    }
+
+    #[test]
+    fn test_try_write() {
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_spin_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        let write = |slot: &mut i64, seq, _: &i32| *slot = seq;
+        assert_eq!(producer.try_write(0..64, write), Ok(()));
+        assert_eq!(producer.try_write(0..1, write), Err(InsufficientCapacity));
+        assert_eq!(producer.try_write(0..0, write), Ok(()));
+        assert!(producer.try_claim(0).unwrap().is_empty());
+
+        let handle = executor.spawn();
+        producer.drain();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_try_write_multi_producer() {
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_spin_wait()
+            .with_multi_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        let write = |slot: &mut i64, seq, _: &i32| *slot = seq;
+        assert_eq!(producer.try_write(0..32, write), Ok(()));
+        assert_eq!(producer.try_write(0..64, write), Err(InsufficientCapacity));
+
+        let handle = executor.spawn();
+        producer.drain();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_ignore_exception_handler() {
+        let count = Arc::new(AtomicI64::new(0));
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events_with_exception_handler(
+                    Faulty {
+                        count: count.clone(),
+                    },
+                    IgnoreExceptionHandler,
+                );
+            })
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        for _ in 0..10 {
+            producer
+                .write(0..10, |slot, seq, _| {
+                    *slot = seq;
+                })
+                .unwrap();
+        }
+        producer.drain();
+        handle.join().unwrap();
+
+        assert_eq!(count.load(Ordering::Relaxed), 99);
+    }
+
+    #[test]
+    fn test_ignore_exception_handler_on_timeout() {
+        struct PanicOnTimeout {
+            timeouts: Arc<AtomicI64>,
+        }
+        impl EventHandler<i64> for PanicOnTimeout {
+            fn handle_event(&mut self, _: &i64, _: Sequence, _: bool) {}
+
+            fn on_timeout(&mut self, _: Sequence) {
+                self.timeouts.fetch_add(1, Ordering::Relaxed);
+                panic!("timeout");
+            }
+        }
+
+        let timeouts = Arc::new(AtomicI64::new(0));
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_wait_strategy(TimeoutBlockingWaitStrategy::with_timeout(
+                Duration::from_millis(1),
+            ))
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events_with_exception_handler(
+                    PanicOnTimeout {
+                        timeouts: timeouts.clone(),
+                    },
+                    IgnoreExceptionHandler,
+                );
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        while timeouts.load(Ordering::Relaxed) < 3 {
+            std::thread::yield_now();
+        }
+        producer
+            .write(0..10, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+        producer.drain();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_fatal_exception_handler_halts_pipeline() {
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Faulty {
+                    count: Default::default(),
+                });
+            })
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        producer
+            .write(0..10, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+        producer.drain();
+
+        let error = handle.join().unwrap_err();
+        assert_eq!(error.failures().len(), 1);
+        let failure = &error.failures()[0];
+        assert_eq!(failure.name(), "Faulty#0");
+        assert_eq!(failure.message(), "faulty event");
+        assert!(failure.last_sequence() < 5);
+    }
+
+    #[test]
+    fn test_failures_named_by_topology() {
+        struct FailingStart;
+        impl EventHandler<i64> for FailingStart {
+            fn handle_event(&mut self, _: &i64, _: Sequence, _: bool) {}
+            fn on_start(&mut self) {
+                panic!("failing start");
+            }
+        }
+
+        let (executor, _producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(FailingStart);
+                b.handle_events(FailingStart);
+            })
+            .build()
+            .unwrap();
+
+        let error = executor.spawn().join().unwrap_err();
+        let names: Vec<_> = error.failures().iter().map(|f| f.name()).collect();
+        assert_eq!(names, ["FailingStart#0", "FailingStart#1"]);
+    }
+
+    #[test]
+    fn test_attached_labels_stay_unique() {
+        struct FailingStart;
+        impl EventHandler<i64> for FailingStart {
+            fn handle_event(&mut self, _: &i64, _: Sequence, _: bool) {}
+            fn on_start(&mut self) {
+                panic!("failing start");
+            }
+        }
+
+        let mut disruptor = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build_disruptor()
+            .unwrap();
+        disruptor.start();
+
+        let mut names = Vec::new();
+        for _ in 0..2 {
+            let attached = disruptor.attach(BatchEventProcessor::create(FailingStart));
+            let error = disruptor.detach(attached).unwrap_err();
+            names.push(error.failures()[0].name().to_string());
+        }
+        assert_eq!(names, ["FailingStart#1", "FailingStart#2"]);
+        disruptor.halt().unwrap();
+    }
+
+    #[test]
+    fn test_lifecycle_hooks() {
+        let shutdowns = Arc::new(AtomicI64::new(0));
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Lifecycle {
+                    thread: None,
+                    shutdowns: shutdowns.clone(),
+                });
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        producer
+            .write(0..10, |slot, seq, _| {
                 *slot = seq;
-            });
+            })
+            .unwrap();
+        producer.drain();
+        handle.join().unwrap();
+
+        assert_eq!(shutdowns.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_worker_pool() {
+        let count = Arc::new(AtomicI64::new(0));
+        let sum = Arc::new(AtomicI64::new(0));
+        let ring_buffer: Arc<RingBuffer<i64>> = Arc::new(RingBuffer::new(4096));
+        let (executor, producer) = dsl::DisrustorBuilder::new(ring_buffer)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events_with_worker_pool((0..4).map(|_| Counter {
+                    count: count.clone(),
+                    sum: sum.clone(),
+                }));
+            })
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        for _ in 0..100 {
+            let buffer: Vec<_> = std::iter::repeat(1).take(1000).collect();
+            producer
+                .write(buffer, |slot, seq, _| {
+                    *slot = seq;
+                })
+                .unwrap();
         }
         producer.drain();
-        handle.join();
+        handle.join().unwrap();
+
+        assert_eq!(count.load(Ordering::Relaxed), 100_000);
+        assert_eq!(sum.load(Ordering::Relaxed), (0..100_000).sum());
+    }
+
+    #[test]
+    fn test_disruptor_shutdown() {
+        let mut disruptor = dsl::DisrustorBuilder::with_ring_buffer(1024)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build_disruptor()
+            .unwrap();
+
+        disruptor.start();
+        for _ in 0..100 {
+            disruptor
+                .producer()
+                .write(0..100, |slot, seq, _| {
+                    *slot = seq;
+                })
+                .unwrap();
+        }
+
+        disruptor.shutdown(Duration::from_secs(10)).unwrap();
+    }
+
+    #[test]
+    fn test_disruptor_shutdown_timeout() {
+        let open = Arc::new(AtomicBool::new(false));
+        let mut disruptor = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Gate { open: open.clone() });
+            })
+            .build_disruptor()
+            .unwrap();
+
+        disruptor.start();
+        disruptor
+            .producer()
+            .write(0..10, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+
+        assert!(matches!(
+            disruptor.shutdown(Duration::from_millis(50)),
+            Err(ShutdownError::Timeout(TimeoutError))
+        ));
+
+        open.store(true, Ordering::Release);
+        disruptor.halt().unwrap();
+    }
+
+    #[test]
+    fn test_disruptor_drop_after_timeout() {
+        let open = Arc::new(AtomicBool::new(false));
+        let mut disruptor = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_multi_producer()
+            .with_barrier(|b| {
+                b.handle_events(Gate { open: open.clone() });
+            })
+            .build_disruptor()
+            .unwrap();
+
+        disruptor.start();
+        disruptor
+            .producer()
+            .write(0..10, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+
+        assert!(matches!(
+            disruptor.shutdown(Duration::from_millis(50)),
+            Err(ShutdownError::Timeout(TimeoutError))
+        ));
+
+        drop(disruptor);
+        open.store(true, Ordering::Release);
+    }
+
+    #[test]
+    fn test_claim() {
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        let mut written = 0;
+        for n in 1..20 {
+            let mut claim = producer.claim(n).unwrap();
+            for slot in claim.iter_mut() {
+                *slot = written;
+                written += 1;
+            }
+            claim.commit();
+        }
+
+        let mut claim = producer.claim(2).unwrap();
+        claim[0] = written;
+        claim[1] = written + 1;
+        drop(claim);
+
+        producer.drain();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_interleaved_claims() {
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        let cursor = producer.sequencer().get_cursor();
+        let mut first = producer.claim(3).unwrap();
+        let mut second = producer.claim(2).unwrap();
+        for (seq, slot) in (second.start()..).zip(second.iter_mut()) {
+            *slot = seq;
+        }
+        second.commit();
+        assert_eq!(cursor.get(), -1);
+
+        for (seq, slot) in (first.start()..).zip(first.iter_mut()) {
+            *slot = seq;
+        }
+        first.commit();
+        assert_eq!(cursor.get(), 4);
+
+        let handle = executor.spawn();
+        producer.drain();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_empty_and_full_claims() {
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_spin_wait()
+            .with_multi_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        assert!(producer.claim(0).unwrap().is_empty());
+        let mut claim = producer.claim(64).unwrap();
+        for (seq, slot) in (claim.start()..).zip(claim.iter_mut()) {
+            *slot = seq;
+        }
+        claim.commit();
+        assert!(producer.try_claim(0).unwrap().is_empty());
+
+        let handle = executor.spawn();
+        producer.drain();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_claim_invalidated_on_panic() {
+        let count = Arc::new(AtomicI64::new(0));
+        let sum = Arc::new(AtomicI64::new(0));
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events_with_worker_pool((0..2).map(|_| Counter {
+                    count: count.clone(),
+                    sum: sum.clone(),
+                }));
+            })
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        producer
+            .write(0..10, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut claim = producer.claim(5).unwrap();
+            claim[0] = -1;
+            panic!("failed to build event");
+        }));
+        assert!(result.is_err());
+
+        producer
+            .write(0..10, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+        producer.drain();
+        handle.join().unwrap();
+
+        assert_eq!(count.load(Ordering::Relaxed), 20);
+        assert_eq!(
+            sum.load(Ordering::Relaxed),
+            (0..10).chain(15..25).sum::<i64>()
+        );
+    }
+
+    #[test]
+    fn test_event_translators() {
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        producer
+            .publish_event(&|slot: &mut i64, seq| *slot = seq, ())
+            .unwrap();
+        producer
+            .publish_event(&|slot: &mut i64, _, value| *slot = value, (1,))
+            .unwrap();
+        producer.publish_event(&Offset, (1, 1)).unwrap();
+        producer
+            .try_publish_event(
+                &|slot: &mut i64, _, a, b, c| *slot = a + b + c,
+                (1i64, 1, 1),
+            )
+            .unwrap();
+        producer
+            .publish_events(&Offset, (0..50).map(|n: i32| (4, n as i64)))
+            .unwrap();
+
+        producer.drain();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_cloned_multi_producer() {
+        fn assert_shareable<P: Clone + Send + Sync>(_: &P) {}
+
+        let count = Arc::new(AtomicI64::new(0));
+        let ring_buffer: Arc<RingBuffer<i64>> = Arc::new(RingBuffer::new(64));
+        let (executor, producer) = dsl::DisrustorBuilder::new(ring_buffer)
+            .with_blocking_wait()
+            .with_multi_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+                b.handle_events(Tap {
+                    count: count.clone(),
+                });
+            })
+            .build()
+            .unwrap();
+        assert_shareable(&producer);
+
+        let handle = executor.spawn();
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let producer = producer.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..1000 {
+                        producer
+                            .publish_event(&|slot: &mut i64, seq| *slot = seq, ())
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for t in threads {
+            t.join().unwrap();
+        }
+        producer.drain();
+        handle.join().unwrap();
+        assert_eq!(count.load(Ordering::Relaxed), 4000);
+    }
+
+    #[test]
+    fn test_dropped_multi_producer_halts() {
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_multi_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        producer.write(0..4, |slot, seq, _| *slot = seq).unwrap();
+        drop(producer);
+        executor.spawn().join().unwrap();
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_producer() {
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(8)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        block_on(async {
+            for _ in 0..1000 {
+                producer
+                    .write_async(0..3, |slot, seq, _| {
+                        *slot = seq;
+                    })
+                    .await
+                    .unwrap();
+
+                let mut claim = producer.claim_async(5).await.unwrap();
+                let start = claim.start();
+                for (idx, slot) in claim.iter_mut().enumerate() {
+                    *slot = start + idx as Sequence;
+                }
+                claim.commit();
+            }
+        });
+
+        producer.drain();
+        handle.join().unwrap();
+    }
+
+    #[cfg(feature = "stream")]
+    #[test]
+    fn test_event_stream() {
+        use futures_core::Stream;
+        use std::{
+            future::Future,
+            pin::Pin,
+            task::{Context, Poll},
+        };
+
+        struct Collect<S: Stream>(S, Vec<S::Item>);
+        impl<S: Stream + Unpin> Future for Collect<S>
+        where
+            S::Item: Unpin,
+        {
+            type Output = Vec<S::Item>;
+
+            fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                loop {
+                    match Pin::new(&mut self.0).poll_next(cx) {
+                        Poll::Ready(Some(item)) => self.1.push(item),
+                        Poll::Ready(None) => return Poll::Ready(std::mem::take(&mut self.1)),
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+            }
+        }
+
+        let ring_buffer: Arc<RingBuffer<i64>> = Arc::new(RingBuffer::new(16));
+        let sequencer =
+            SingleProducerSequencer::new(ring_buffer.buffer_size(), BlockingWaitStrategy::new());
+
+        let gating_sequences = vec![sequencer.get_cursor()];
+        let barrier = sequencer.create_barrier(&gating_sequences);
+        let stream = EventStream::with_mapper(barrier, ring_buffer.clone(), |event, seq| {
+            assert_eq!(*event, seq);
+            *event * 2
+        });
+        sequencer.add_gating_sequence(&stream.get_cursor());
+
+        let producer = Producer::new(ring_buffer, sequencer);
+        let writer = std::thread::spawn(move || {
+            for _ in 0..100 {
+                producer
+                    .write(0..10, |slot, seq, _| {
+                        *slot = seq;
+                    })
+                    .unwrap();
+            }
+            producer.drain();
+        });
+
+        let items = block_on(Collect(stream, Vec::new()));
+        writer.join().unwrap();
+
+        assert_eq!(items, (0..1000).map(|n| n * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_event_poller() {
+        let mut first = None;
+        let mut second = None;
+        let (_, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                first = Some(b.create_event_poller());
+            })
+            .with_barrier(|b| {
+                second = Some(b.create_event_poller());
+            })
+            .build()
+            .unwrap();
+        let (mut first, mut second) = (first.unwrap(), second.unwrap());
+
+        let check = |event: &i64, seq, _| {
+            assert_eq!(*event, seq);
+            true
+        };
+        assert_eq!(first.poll(check), PollState::Idle);
+
+        producer
+            .write(0..10, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+        assert_eq!(second.poll(check), PollState::Gating);
+
+        let mut seen = Vec::new();
+        let state = first.poll(|_, seq, _| {
+            seen.push(seq);
+            seq < 4
+        });
+        assert_eq!(state, PollState::Processing);
+        assert_eq!(seen, vec![0, 1, 2, 3, 4]);
+
+        assert_eq!(first.poll(check), PollState::Processing);
+        assert_eq!(first.poll(check), PollState::Idle);
+        assert_eq!(second.poll(check), PollState::Processing);
+        assert_eq!(second.get_cursor().get(), 9);
+        assert_eq!(second.poll(check), PollState::Idle);
+    }
+
+    #[test]
+    fn test_attach_and_detach_processor() {
+        let mut disruptor = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build_disruptor()
+            .unwrap();
+
+        disruptor.start();
+        for _ in 0..2 {
+            disruptor
+                .producer()
+                .write(0..50, |slot, seq, _| {
+                    *slot = seq;
+                })
+                .unwrap();
+        }
+
+        let count = Arc::new(AtomicI64::new(0));
+        let tap = disruptor.attach(BatchEventProcessor::create(Tap {
+            count: count.clone(),
+        }));
+        assert_eq!(tap.get_cursor().get(), 99);
+
+        for _ in 0..2 {
+            disruptor
+                .producer()
+                .write(0..50, |slot, seq, _| {
+                    *slot = seq;
+                })
+                .unwrap();
+        }
+        while tap.get_cursor().get() < 199 {
+            std::thread::yield_now();
+        }
+        disruptor.detach(tap).unwrap();
+        assert_eq!(count.load(Ordering::Relaxed), 100);
+
+        for _ in 0..10 {
+            disruptor
+                .producer()
+                .write(0..50, |slot, seq, _| {
+                    *slot = seq;
+                })
+                .unwrap();
+        }
+        disruptor.shutdown(Duration::from_secs(10)).unwrap();
+        assert_eq!(count.load(Ordering::Relaxed), 100);
+    }
+
+    #[test]
+    fn test_dropped_processor_stops_gating() {
+        let mut disruptor = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .build_disruptor()
+            .unwrap();
+
+        disruptor.start();
+        let tap = disruptor.attach(BatchEventProcessor::create(Tap {
+            count: Arc::new(AtomicI64::new(0)),
+        }));
+        assert_eq!(disruptor.metrics().handlers.len(), 2);
+        drop(tap);
+        assert_eq!(disruptor.metrics().handlers.len(), 1);
+
+        for _ in 0..10 {
+            disruptor
+                .producer()
+                .write(0..50, |slot, seq, _| {
+                    *slot = seq;
+                })
+                .unwrap();
+        }
+        disruptor.shutdown(Duration::from_secs(10)).unwrap();
+    }
+
+    #[test]
+    fn test_handler_dependencies() {
+        let journal = Arc::new(AtomicI64::new(-1));
+        let replicate = Arc::new(AtomicI64::new(-1));
+        let logic = Arc::new(AtomicI64::new(-1));
+        let audit = Arc::new(AtomicI64::new(-1));
+        let stage = |last: &Arc<AtomicI64>, upstream: &[&Arc<AtomicI64>]| Stage {
+            last: last.clone(),
+            upstream: upstream.iter().map(|u| Arc::clone(u)).collect(),
+        };
+
+        let mut journal_id = None;
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                let j = b.handle_events(stage(&journal, &[]));
+                let r = b.handle_events(stage(&replicate, &[]));
+                b.after(&[j, r])
+                    .handle_events(stage(&logic, &[&journal, &replicate]));
+                journal_id = Some(j);
+            })
+            .with_barrier(|b| {
+                b.after(&[journal_id.unwrap()])
+                    .handle_events(stage(&audit, &[&journal]));
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        for _ in 0..100 {
+            producer
+                .write(0..50, |slot, seq, _| {
+                    *slot = seq;
+                })
+                .unwrap();
+        }
+        producer.drain();
+        handle.join().unwrap();
+
+        for last in [&journal, &replicate, &logic, &audit] {
+            assert_eq!(last.load(Ordering::Acquire), 4999);
+        }
+    }
+
+    #[test]
+    fn test_topology_validation() {
+        let stage = || Stage {
+            last: Default::default(),
+            upstream: Vec::new(),
+        };
+
+        let mut foreign = None;
+        let result = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                foreign = Some(b.handle_events(stage()));
+            })
+            .with_barrier(|_| {})
+            .validate();
+        assert_eq!(result.err(), Some(vec![dsl::BuildError::EmptyBarrier(1)]));
+
+        let foreign = foreign.unwrap();
+        let result = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.after(&[foreign]).handle_events(stage());
+            })
+            .with_barrier(|_| {})
+            .validate();
+        assert_eq!(
+            result.err(),
+            Some(vec![
+                dsl::BuildError::UnknownHandler(foreign),
+                dsl::BuildError::EmptyBarrier(1),
+            ])
+        );
+
+        let result = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                let id = b.handle_events(stage());
+                b.configure(foreign, ThreadConfig::new().name("stage"));
+                b.after(&[id]).handle_events(stage());
+            })
+            .build();
+        assert_eq!(result.err(), Some(dsl::BuildError::UnknownHandler(foreign)));
+
+        let result = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(stage());
+            })
+            .with_barrier(|_| {})
+            .build();
+        assert_eq!(result.err(), Some(dsl::BuildError::EmptyBarrier(1)));
+
+        let mut ids = Vec::new();
+        let result = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                ids.push(b.handle_events(stage()));
+            })
+            .with_barrier(|b| {
+                ids.push(b.handle_events_with_worker_pool(Vec::<Counter>::new()));
+            })
+            .validate();
+        assert_eq!(
+            result.err(),
+            Some(vec![
+                dsl::BuildError::EmptyWorkerPool(ids[1]),
+                dsl::BuildError::Ungated(ids[0]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_topology_to_dot() {
+        let dot = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                let checker = b.handle_events(Checker {});
+                b.handle_events_with_worker_pool((0..2).map(|_| Counter {
+                    count: Default::default(),
+                    sum: Default::default(),
+                }));
+                b.after(&[checker]).handle_events_mut(Checker {});
+            })
+            .with_barrier(|b| {
+                b.handle_events(Checker {});
+            })
+            .validate()
+            .unwrap()
+            .to_dot();
+
+        assert_eq!(
+            dot,
+            "digraph disrustor {\n    producer [shape=box];\n    h0 [label=\"Checker\"];\n    \
+             h1 [label=\"Counter x2\"];\n    h2 [label=\"Checker\"];\n    \
+             h3 [label=\"Checker\"];\n    producer -> h0;\n    producer -> h1;\n    \
+             h0 -> h2;\n    h0 -> h3;\n    h1 -> h3;\n    h2 -> h3;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_ring_buffer_factory() {
+        struct Frame {
+            bytes: Vec<u8>,
+        }
+
+        let mut poller = None;
+        let (_, producer) = dsl::DisrustorBuilder::with_ring_buffer_factory(16, |_| Frame {
+            bytes: Vec::with_capacity(32),
+        })
+        .with_blocking_wait()
+        .with_single_producer()
+        .with_barrier(|b| {
+            poller = Some(b.create_event_poller());
+        })
+        .build()
+        .unwrap();
+        let mut poller = poller.unwrap();
+
+        producer
+            .write(0..8u8, |frame, _, n| {
+                frame.bytes.clear();
+                frame.bytes.extend((0..*n).map(|_| *n));
+            })
+            .unwrap();
+
+        let state = poller.poll(|frame, seq, _| {
+            assert_eq!(frame.bytes, vec![seq as u8; seq as usize]);
+            assert_eq!(frame.bytes.capacity(), 32);
+            true
+        });
+        assert_eq!(state, PollState::Processing);
+        assert_eq!(poller.get_cursor().get(), 7);
+    }
+
+    #[test]
+    fn test_thread_config() {
+        struct Named(std::sync::mpsc::Sender<String>);
+        impl EventHandler<i64> for Named {
+            fn handle_event(&mut self, _: &i64, _: Sequence, _: bool) {}
+            fn on_start(&mut self) {
+                let name = std::thread::current()
+                    .name()
+                    .unwrap_or("<unnamed>")
+                    .to_string();
+                self.0.send(name).unwrap();
+            }
+        }
+        impl WorkHandler<i64> for Named {
+            fn handle_event(&mut self, _: &i64, _: Sequence) {}
+            fn on_start(&mut self) {
+                EventHandler::<i64>::on_start(self);
+            }
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut journal = None;
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                journal = Some(b.handle_events(Named(tx.clone())));
+                let pool = b.handle_events_with_worker_pool((0..2).map(|_| Named(tx.clone())));
+                b.configure(pool, ThreadConfig::new().name("pool"));
+            })
+            .with_barrier(|b| {
+                b.handle_events(Named(tx.clone()));
+                let config = ThreadConfig::new()
+                    .name("journal")
+                    .stack_size(256 * 1024)
+                    .affinity(vec![0])
+                    .priority(0);
+                b.configure(journal.unwrap(), config);
+            })
+            .build()
+            .unwrap();
+
+        let handle = executor.spawn();
+        let mut names: Vec<_> = rx.iter().take(4).collect();
+        names.sort();
+        assert_eq!(names, vec!["<unnamed>", "journal", "pool-0", "pool-1"]);
+
+        producer.drain();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_run_scoped() {
+        struct Borrowing<'a> {
+            last: &'a AtomicI64,
+        }
+        impl<'a> EventHandler<i64> for Borrowing<'a> {
+            fn handle_event(&mut self, data: &i64, sequence: Sequence, _: bool) {
+                assert_eq!(*data, sequence);
+                self.last.store(sequence, Ordering::Release);
+            }
+        }
+
+        let first = AtomicI64::new(-1);
+        let second = AtomicI64::new(-1);
+        let written = dsl::DisrustorBuilder::with_ring_buffer(64)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Borrowing { last: &first });
+            })
+            .with_barrier(|b| {
+                b.handle_events(Borrowing { last: &second });
+            })
+            .run_scoped(|producer| {
+                for _ in 0..100 {
+                    producer
+                        .write(0..50, |slot, seq, _| {
+                            *slot = seq;
+                        })
+                        .unwrap();
+                }
+                5000
+            })
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(written, 5000);
+        assert_eq!(first.load(Ordering::Acquire), 4999);
+        assert_eq!(second.load(Ordering::Acquire), 4999);
+    }
+
+    #[test]
+    fn test_run_scoped_reports_handler_panic() {
+        let result = dsl::DisrustorBuilder::with_ring_buffer(8)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Faulty {
+                    count: Default::default(),
+                });
+            })
+            .run_scoped(|producer| {
+                for _ in 0..100 {
+                    producer.write(0..4, |slot, seq, _| *slot = seq)?;
+                }
+                Ok::<_, HaltedError>(())
+            })
+            .unwrap();
+
+        let error = result.unwrap_err();
+        assert_eq!(error.failures()[0].message(), "faulty event");
+    }
+
+    #[test]
+    fn test_cooperative_executor_reports_handler_panic() {
+        let count = Arc::new(AtomicI64::new(0));
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(16)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Faulty {
+                    count: count.clone(),
+                });
+            })
+            .build_with_executor::<CooperativeExecutor>()
+            .unwrap();
+
+        let mut handle = executor.spawn();
+        producer
+            .write(0..10, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+        assert_eq!(handle.run_until_idle(), StepState::Halted);
+        assert_eq!(count.load(Ordering::Relaxed), 5);
+
+        let error = handle.join().unwrap_err();
+        let failure = &error.failures()[0];
+        assert_eq!(failure.name(), "Faulty#0");
+        assert_eq!(failure.message(), "faulty event");
+        assert_eq!(failure.last_sequence(), -1);
+    }
+
+    #[test]
+    fn test_cooperative_executor() {
+        let first = Arc::new(AtomicI64::new(0));
+        let second = Arc::new(AtomicI64::new(0));
+        let shutdowns = Arc::new(AtomicI64::new(0));
+        let (executor, producer) = dsl::DisrustorBuilder::with_ring_buffer(16)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Tap {
+                    count: first.clone(),
+                });
+                b.handle_events(Lifecycle {
+                    thread: None,
+                    shutdowns: shutdowns.clone(),
+                });
+            })
+            .with_barrier(|b| {
+                b.handle_events(Tap {
+                    count: second.clone(),
+                });
+            })
+            .build_with_executor::<CooperativeExecutor>()
+            .unwrap();
+
+        let mut handle = executor.spawn();
+        assert_eq!(handle.step(), StepState::Idle);
+
+        producer
+            .write(0..10, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+        assert_eq!(first.load(Ordering::Relaxed), 0);
+        assert_eq!(handle.step(), StepState::Processed);
+        assert_eq!(first.load(Ordering::Relaxed), 10);
+        assert_eq!(second.load(Ordering::Relaxed), 10);
+
+        for _ in 0..3 {
+            producer
+                .write(0..12, |slot, seq, _| {
+                    *slot = seq;
+                })
+                .unwrap();
+            assert_eq!(handle.run_until_idle(), StepState::Idle);
+        }
+        assert_eq!(second.load(Ordering::Relaxed), 46);
+
+        producer.drain();
+        assert_eq!(handle.step(), StepState::Halted);
+        handle.join().unwrap();
+        assert_eq!(shutdowns.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_cooperative_disruptor() {
+        let count = Arc::new(AtomicI64::new(0));
+        let mut disruptor = dsl::DisrustorBuilder::with_ring_buffer(16)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Tap {
+                    count: count.clone(),
+                });
+            })
+            .build_disruptor_with_executor::<CooperativeExecutor>()
+            .unwrap();
+
+        assert!(disruptor.handle_mut().is_none());
+        disruptor.start();
+        disruptor
+            .producer()
+            .write(0..10, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+
+        let handle = disruptor.handle_mut().unwrap();
+        assert_eq!(handle.run_until_idle(), StepState::Idle);
+        assert_eq!(count.load(Ordering::Relaxed), 10);
+
+        let mut tap = disruptor.attach(BatchEventProcessor::create(Tap {
+            count: count.clone(),
+        }));
+        disruptor
+            .producer()
+            .write(0..4, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+        disruptor.handle_mut().unwrap().run_until_idle();
+        assert_eq!(count.load(Ordering::Relaxed), 14);
+
+        tap.handle_mut().unwrap().run_until_idle();
+        assert_eq!(count.load(Ordering::Relaxed), 18);
+        disruptor.detach(tap).unwrap();
+        disruptor.halt().unwrap();
+    }
+
+    #[test]
+    fn test_disruptor_metrics() {
+        let count = Arc::new(AtomicI64::new(0));
+        let open = Arc::new(AtomicBool::new(false));
+        let mut disruptor = dsl::DisrustorBuilder::with_ring_buffer(16)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                b.handle_events(Tap {
+                    count: count.clone(),
+                });
+            })
+            .with_barrier(|b| {
+                b.handle_events(Gate { open: open.clone() });
+            })
+            .build_disruptor()
+            .unwrap();
+
+        disruptor.start();
+        disruptor
+            .producer()
+            .write(0..10, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+        while disruptor.metrics().handlers[0].sequence < 9 {
+            std::thread::yield_now();
+        }
+
+        let metrics = disruptor.metrics();
+        assert_eq!(metrics.sequencer.cursor, 9);
+        assert_eq!(metrics.sequencer.gating_sequences, vec![-1]);
+        assert_eq!(metrics.sequencer.remaining_capacity, 6);
+        assert_eq!(metrics.sequencer.capacity_waits, 0);
+        let names: Vec<_> = metrics.handlers.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(names, vec!["Tap", "Gate"]);
+        assert_eq!(
+            (metrics.handlers[0].sequence, metrics.handlers[0].lag),
+            (9, 0)
+        );
+        assert_eq!(metrics.handlers[0].events, 10);
+        assert_eq!(
+            (metrics.handlers[1].sequence, metrics.handlers[1].lag),
+            (-1, 10)
+        );
+        assert_eq!(metrics.handlers[1].events, 0);
+
+        let opener = {
+            let open = open.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                open.store(true, Ordering::Release);
+            })
+        };
+        disruptor
+            .producer()
+            .write(10..20, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+        opener.join().unwrap();
+        disruptor.shutdown(Duration::from_secs(10)).unwrap();
+
+        let metrics = disruptor.metrics();
+        assert_eq!(metrics.sequencer.capacity_waits, 1);
+        assert_eq!(metrics.sequencer.remaining_capacity, 16);
+        assert_eq!(metrics.handlers[1].events, 20);
+        assert_eq!(metrics.handlers[1].lag, 0);
+    }
+
+    #[test]
+    fn test_poller_metrics() {
+        let mut poller = None;
+        let mut disruptor = dsl::DisrustorBuilder::with_ring_buffer(16)
+            .with_blocking_wait()
+            .with_single_producer()
+            .with_barrier(|b| {
+                poller = Some(b.create_event_poller());
+            })
+            .build_disruptor()
+            .unwrap();
+        let mut poller = poller.unwrap();
+
+        disruptor.start();
+        disruptor
+            .producer()
+            .write(0..5, |slot, seq, _| {
+                *slot = seq;
+            })
+            .unwrap();
+        assert_eq!(poller.poll(|_, _, _| true), PollState::Processing);
+
+        let metrics = disruptor.metrics();
+        assert_eq!(metrics.handlers[0].name, "EventPoller");
+        assert_eq!(
+            (metrics.handlers[0].events, metrics.handlers[0].batches),
+            (5, 1)
+        );
+        disruptor.halt().unwrap();
     }
 }
@@ -0,0 +1,75 @@
+use crate::prelude::*;
+use std::{
+    marker::PhantomData,
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+};
+
+pub struct EventPoller<B: SequenceBarrier, D: DataProvider<T>, T> {
+    barrier: B,
+    data_provider: Arc<D>,
+    cursor: Arc<AtomicSequence>,
+    sequencer_cursor: Arc<AtomicSequence>,
+    counters: Arc<ProcessorCounters>,
+    _element: PhantomData<T>,
+}
+
+impl<B: SequenceBarrier, D: DataProvider<T>, T> EventPoller<B, D, T> {
+    pub fn new(barrier: B, data_provider: Arc<D>, sequencer_cursor: Arc<AtomicSequence>) -> Self {
+        EventPoller {
+            barrier,
+            data_provider,
+            cursor: Default::default(),
+            sequencer_cursor,
+            counters: Default::default(),
+            _element: Default::default(),
+        }
+    }
+
+    pub fn get_cursor(&self) -> Arc<AtomicSequence> {
+        self.cursor.clone()
+    }
+
+    pub fn get_counters(&self) -> Arc<ProcessorCounters> {
+        self.counters.clone()
+    }
+
+    pub fn poll<F: FnMut(&T, Sequence, bool) -> bool>(&mut self, mut f: F) -> PollState {
+        let current = self.cursor.get();
+        let next = current + 1;
+        let available = self.barrier.available();
+
+        if next > available {
+            return if self.sequencer_cursor.get() >= next {
+                PollState::Gating
+            } else {
+                PollState::Idle
+            };
+        }
+
+        let mut processed = current;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            for sequence in next..=available {
+                if !self.barrier.is_invalid(sequence) {
+                    let event = unsafe { self.data_provider.get(sequence) };
+                    if !f(event, sequence, sequence == available) {
+                        processed = sequence;
+                        break;
+                    }
+                }
+                processed = sequence;
+            }
+        }));
+
+        if processed > current {
+            self.counters.record_batch((processed - current) as u64);
+        }
+        self.cursor.set(processed);
+        self.barrier.signal();
+        if let Err(error) = result {
+            panic::resume_unwind(error);
+        }
+
+        PollState::Processing
+    }
+}
@@ -1,8 +1,11 @@
+use crate::{thread::ThreadConfig, utils::panic_message};
 use std::{
+    any::Any,
     borrow::Borrow,
+    fmt,
     sync::{
-        atomic::{AtomicI64, Ordering},
-        Arc,
+        atomic::{AtomicI64, AtomicPtr, AtomicU64, Ordering},
+        Arc, Mutex,
     },
 };
 
@@ -53,30 +56,289 @@ impl From<Sequence> for AtomicSequence {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitError {
+    Alerted,
+    Timeout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollState {
+    Processing,
+    Gating,
+    Idle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepState {
+    Processed,
+    Idle,
+    Halted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequencerStats {
+    pub cursor: Sequence,
+    pub gating_sequences: Vec<Sequence>,
+    pub buffer_size: usize,
+    pub remaining_capacity: usize,
+    pub capacity_waits: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct ProcessorCounters {
+    events: AtomicU64,
+    batches: AtomicU64,
+}
+
+impl ProcessorCounters {
+    pub fn record_batch(&self, events: u64) {
+        self.events.fetch_add(events, Ordering::Relaxed);
+        self.batches.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn events(&self) -> u64 {
+        self.events.load(Ordering::Relaxed)
+    }
+
+    pub fn batches(&self) -> u64 {
+        self.batches.load(Ordering::Relaxed)
+    }
+}
+
+// Readers load the current snapshot without locking. Writers publish a new
+// snapshot and keep the old ones alive until the set is dropped, since a
+// reader may still be iterating them; attach and detach are rare enough for
+// that to stay small.
+pub struct GatingSequences {
+    current: AtomicPtr<Vec<Arc<AtomicSequence>>>,
+    snapshots: Mutex<Vec<Arc<Vec<Arc<AtomicSequence>>>>>,
+}
+
+impl Default for GatingSequences {
+    fn default() -> Self {
+        let initial = Arc::new(Vec::new());
+        GatingSequences {
+            current: AtomicPtr::new(Arc::as_ptr(&initial) as *mut _),
+            snapshots: Mutex::new(vec![initial]),
+        }
+    }
+}
+
+impl GatingSequences {
+    pub fn load(&self) -> &[Arc<AtomicSequence>] {
+        unsafe { &*self.current.load(Ordering::Acquire) }
+    }
+
+    pub fn add(&self, gating_sequence: &Arc<AtomicSequence>, cursor: &AtomicSequence) {
+        self.update(|sequences| {
+            let current = cursor.get();
+            if gating_sequence.get() < current {
+                gating_sequence.set(current);
+            }
+            sequences.push(gating_sequence.clone());
+        });
+    }
+
+    pub fn remove(&self, gating_sequence: &Arc<AtomicSequence>) -> bool {
+        let mut removed = false;
+        self.update(|sequences| {
+            let len = sequences.len();
+            sequences.retain(|s| !Arc::ptr_eq(s, gating_sequence));
+            removed = sequences.len() != len;
+        });
+        removed
+    }
+
+    fn update<F: FnOnce(&mut Vec<Arc<AtomicSequence>>)>(&self, f: F) {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        let mut sequences = self.load().to_vec();
+        f(&mut sequences);
+        let next = Arc::new(sequences);
+        self.current
+            .store(Arc::as_ptr(&next) as *mut _, Ordering::Release);
+        snapshots.push(next);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InsufficientCapacity;
+
+impl fmt::Display for InsufficientCapacity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "insufficient capacity in ring buffer")
+    }
+}
+
+impl std::error::Error for InsufficientCapacity {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutError;
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out waiting for consumers to drain")
+    }
+}
+
+impl std::error::Error for TimeoutError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HaltedError;
+
+impl fmt::Display for HaltedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sequencer has been halted")
+    }
+}
+
+impl std::error::Error for HaltedError {}
+
+#[derive(Debug)]
+pub struct ProcessorFailure {
+    name: String,
+    payload: Box<dyn Any + Send>,
+    last_sequence: Sequence,
+}
+
+impl ProcessorFailure {
+    pub fn new(name: String, payload: Box<dyn Any + Send>, last_sequence: Sequence) -> Self {
+        ProcessorFailure {
+            name,
+            payload,
+            last_sequence,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn payload(&self) -> &(dyn Any + Send) {
+        self.payload.as_ref()
+    }
+
+    pub fn message(&self) -> &str {
+        panic_message(self.payload.as_ref())
+    }
+
+    pub fn last_sequence(&self) -> Sequence {
+        self.last_sequence
+    }
+
+    pub fn into_payload(self) -> Box<dyn Any + Send> {
+        self.payload
+    }
+}
+
+#[derive(Debug)]
+pub struct ExecutorError {
+    failures: Vec<ProcessorFailure>,
+}
+
+impl ExecutorError {
+    pub fn new(failures: Vec<ProcessorFailure>) -> Self {
+        ExecutorError { failures }
+    }
+
+    pub fn failures(&self) -> &[ProcessorFailure] {
+        &self.failures
+    }
+
+    pub fn into_failures(self) -> Vec<ProcessorFailure> {
+        self.failures
+    }
+}
+
+impl fmt::Display for ExecutorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} event processor(s) panicked", self.failures.len())?;
+        for failure in &self.failures {
+            write!(
+                f,
+                "; {} after sequence {}: {}",
+                failure.name,
+                failure.last_sequence,
+                failure.message()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ExecutorError {}
+
+#[derive(Debug)]
+pub enum ShutdownError {
+    Timeout(TimeoutError),
+    Executor(ExecutorError),
+}
+
+impl fmt::Display for ShutdownError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShutdownError::Timeout(error) => error.fmt(f),
+            ShutdownError::Executor(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ShutdownError {}
+
+impl From<TimeoutError> for ShutdownError {
+    fn from(error: TimeoutError) -> Self {
+        ShutdownError::Timeout(error)
+    }
+}
+
+impl From<ExecutorError> for ShutdownError {
+    fn from(error: ExecutorError) -> Self {
+        ShutdownError::Executor(error)
+    }
+}
+
 pub trait SequenceBarrier: Send + Sync {
-    fn wait_for(&self, sequence: Sequence) -> Option<Sequence>;
+    fn wait_for(&self, sequence: Sequence) -> Result<Sequence, WaitError>;
+    fn available(&self) -> Sequence;
+    fn is_invalid(&self, sequence: Sequence) -> bool;
+    fn is_alerted(&self) -> bool;
+    #[cfg(feature = "async")]
+    fn register_waker(&self, waker: &std::task::Waker);
     fn signal(&self);
+    fn alert(&self);
+    fn halt(&self);
 }
 
 pub trait Sequencer {
-    type Barrier: SequenceBarrier;
+    type Barrier: SequenceBarrier + Clone;
 
-    fn next(&self, count: usize) -> (Sequence, Sequence);
+    fn next(&self, count: usize) -> Result<(Sequence, Sequence), HaltedError>;
+    fn try_next(&self, count: usize) -> Result<(Sequence, Sequence), InsufficientCapacity>;
     fn publish(&self, lo: Sequence, hi: Sequence);
-    fn create_barrier(&mut self, gating_sequences: &[Arc<AtomicSequence>]) -> Self::Barrier;
-    fn add_gating_sequence(&mut self, gating_sequence: &Arc<AtomicSequence>);
+    fn invalidate(&self, lo: Sequence, hi: Sequence);
+    fn create_barrier(&self, gating_sequences: &[Arc<AtomicSequence>]) -> Self::Barrier;
+    fn add_gating_sequence(&self, gating_sequence: &Arc<AtomicSequence>);
+    fn remove_gating_sequence(&self, gating_sequence: &Arc<AtomicSequence>) -> bool;
+    fn gating_sequences(&self) -> Arc<GatingSequences>;
     fn get_cursor(&self) -> Arc<AtomicSequence>;
+    fn stats(&self) -> SequencerStats;
+    #[cfg(feature = "async")]
+    fn poll_next(
+        &self,
+        count: usize,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(Sequence, Sequence), HaltedError>>;
+    fn halt(&self);
     fn drain(self);
 }
 
 pub trait WaitStrategy: Send + Sync {
-    fn new() -> Self;
     fn wait_for<F: Fn() -> bool, S: Borrow<AtomicSequence>>(
         &self,
         sequence: Sequence,
         dependencies: &[S],
         check_alert: F,
-    ) -> Option<Sequence>;
+    ) -> Result<Sequence, WaitError>;
     fn signal(&self);
 }
 
@@ -95,36 +357,143 @@ pub trait EventProcessorMut<'a, T> {
         data_provider: Arc<D>,
     ) -> Box<dyn Runnable + 'a>;
     fn get_cursor(&self) -> Arc<AtomicSequence>;
+    fn get_counters(&self) -> Arc<ProcessorCounters> {
+        Default::default()
+    }
 }
 
 pub trait EventProcessor<'a, T>: EventProcessorMut<'a, T> {}
 
 pub trait Runnable: Send {
     fn run(self: Box<Self>);
+    fn start(&mut self) {}
+    fn step(&mut self) -> StepState;
+    fn shutdown(&mut self) {}
+    fn name(&self) -> String;
+    fn get_cursor(&self) -> Arc<AtomicSequence>;
 }
 
 pub trait EventHandler<T> {
     fn handle_event(&mut self, event: &T, sequence: Sequence, eob: bool);
+    fn on_timeout(&mut self, _sequence: Sequence) {}
+    fn on_start(&mut self) {}
+    fn on_shutdown(&mut self) {}
 }
 
 pub trait EventHandlerMut<T> {
     fn handle_event(&mut self, event: &mut T, sequence: Sequence, eob: bool);
+    fn on_timeout(&mut self, _sequence: Sequence) {}
+    fn on_start(&mut self) {}
+    fn on_shutdown(&mut self) {}
+}
+
+pub trait ExceptionHandler<T> {
+    fn handle_event_exception(&mut self, error: Box<dyn Any + Send>, sequence: Sequence, event: &T);
+    fn on_timeout_exception(&mut self, error: Box<dyn Any + Send>, sequence: Sequence);
+    fn on_start_exception(&mut self, error: Box<dyn Any + Send>);
+    fn on_shutdown_exception(&mut self, error: Box<dyn Any + Send>);
+}
+
+pub trait WorkHandler<T> {
+    fn handle_event(&mut self, event: &T, sequence: Sequence);
+    fn on_start(&mut self) {}
+    fn on_shutdown(&mut self) {}
+}
+
+pub trait EventTranslator<T> {
+    fn translate_to(&self, event: &mut T, sequence: Sequence);
+}
+
+pub trait EventTranslatorOneArg<T, A> {
+    fn translate_to(&self, event: &mut T, sequence: Sequence, arg0: A);
+}
+
+pub trait EventTranslatorTwoArg<T, A, B> {
+    fn translate_to(&self, event: &mut T, sequence: Sequence, arg0: A, arg1: B);
+}
+
+pub trait EventTranslatorThreeArg<T, A, B, C> {
+    fn translate_to(&self, event: &mut T, sequence: Sequence, arg0: A, arg1: B, arg2: C);
+}
+
+impl<T, F: Fn(&mut T, Sequence)> EventTranslator<T> for F {
+    fn translate_to(&self, event: &mut T, sequence: Sequence) {
+        self(event, sequence)
+    }
+}
+
+impl<T, A, F: Fn(&mut T, Sequence, A)> EventTranslatorOneArg<T, A> for F {
+    fn translate_to(&self, event: &mut T, sequence: Sequence, arg0: A) {
+        self(event, sequence, arg0)
+    }
+}
+
+impl<T, A, B, F: Fn(&mut T, Sequence, A, B)> EventTranslatorTwoArg<T, A, B> for F {
+    fn translate_to(&self, event: &mut T, sequence: Sequence, arg0: A, arg1: B) {
+        self(event, sequence, arg0, arg1)
+    }
+}
+
+impl<T, A, B, C, F: Fn(&mut T, Sequence, A, B, C)> EventTranslatorThreeArg<T, A, B, C> for F {
+    fn translate_to(&self, event: &mut T, sequence: Sequence, arg0: A, arg1: B, arg2: C) {
+        self(event, sequence, arg0, arg1, arg2)
+    }
+}
+
+pub trait Translator<T, Args> {
+    fn translate(&self, event: &mut T, sequence: Sequence, args: Args);
+}
+
+impl<T, X: EventTranslator<T>> Translator<T, ()> for X {
+    fn translate(&self, event: &mut T, sequence: Sequence, _: ()) {
+        self.translate_to(event, sequence)
+    }
+}
+
+impl<T, A, X: EventTranslatorOneArg<T, A>> Translator<T, (A,)> for X {
+    fn translate(&self, event: &mut T, sequence: Sequence, (arg0,): (A,)) {
+        self.translate_to(event, sequence, arg0)
+    }
+}
+
+impl<T, A, B, X: EventTranslatorTwoArg<T, A, B>> Translator<T, (A, B)> for X {
+    fn translate(&self, event: &mut T, sequence: Sequence, (arg0, arg1): (A, B)) {
+        self.translate_to(event, sequence, arg0, arg1)
+    }
+}
+
+impl<T, A, B, C, X: EventTranslatorThreeArg<T, A, B, C>> Translator<T, (A, B, C)> for X {
+    fn translate(&self, event: &mut T, sequence: Sequence, (arg0, arg1, arg2): (A, B, C)) {
+        self.translate_to(event, sequence, arg0, arg1, arg2)
+    }
 }
 
 pub trait EventProcessorExecutor<'a> {
     type Handle: ExecutorHandle;
     fn with_runnables(items: Vec<Box<dyn Runnable + 'a>>) -> Self;
+    fn with_configured_runnables(items: Vec<(Box<dyn Runnable + 'a>, ThreadConfig)>) -> Self
+    where
+        Self: Sized,
+    {
+        Self::with_runnables(items.into_iter().map(|(r, _)| r).collect())
+    }
     fn spawn(self) -> Self::Handle;
 }
 
 pub trait ExecutorHandle {
-    fn join(self);
+    fn join(self) -> Result<(), ExecutorError>;
 }
 
 pub trait EventProducer<'a> {
     type Item;
 
-    fn write<F, U, I, E>(&self, items: I, f: F)
+    fn write<F, U, I, E>(&self, items: I, f: F) -> Result<(), HaltedError>
+    where
+        I: IntoIterator<Item = U, IntoIter = E>,
+        E: ExactSizeIterator<Item = U>,
+        F: Fn(&mut Self::Item, Sequence, &U);
+
+    fn try_write<F, U, I, E>(&self, items: I, f: F) -> Result<(), InsufficientCapacity>
     where
         I: IntoIterator<Item = U, IntoIter = E>,
         E: ExactSizeIterator<Item = U>,
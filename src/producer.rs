@@ -1,9 +1,17 @@
 use crate::{barrier::*, prelude::*, utils::*};
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::ops::{Index, IndexMut};
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
     Arc,
 };
+#[cfg(feature = "async")]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
 pub struct Producer<D: DataProvider<T>, T, S: Sequencer> {
     sequencer: S,
@@ -11,14 +19,28 @@ pub struct Producer<D: DataProvider<T>, T, S: Sequencer> {
     _element: std::marker::PhantomData<T>,
 }
 
+impl<D: DataProvider<T>, T, S: Sequencer + Clone> Clone for Producer<D, T, S> {
+    fn clone(&self) -> Self {
+        Producer {
+            sequencer: self.sequencer.clone(),
+            data_provider: self.data_provider.clone(),
+            _element: Default::default(),
+        }
+    }
+}
+
 pub struct SingleProducerSequencer<W: WaitStrategy> {
     cursor: Arc<AtomicSequence>,
     next_write_sequence: Cell<Sequence>,
     cached_available_sequence: Cell<Sequence>,
+    pending_ranges: RefCell<BTreeMap<Sequence, Sequence>>,
     wait_strategy: Arc<W>,
-    gating_sequences: Vec<Arc<AtomicSequence>>,
+    gating_sequences: Arc<GatingSequences>,
     buffer_size: usize,
     is_done: Arc<AtomicBool>,
+    invalid_slots: Arc<InvalidSlots>,
+    wakers: Arc<WakerSet>,
+    capacity_waits: AtomicU64,
 }
 
 impl<W: WaitStrategy> SingleProducerSequencer<W> {
@@ -26,60 +48,174 @@ impl<W: WaitStrategy> SingleProducerSequencer<W> {
         SingleProducerSequencer {
             cursor: Arc::new(AtomicSequence::default()),
             next_write_sequence: Cell::new(0),
-            cached_available_sequence: Cell::new(Sequence::default()),
+            cached_available_sequence: Cell::new(-1),
+            pending_ranges: Default::default(),
             wait_strategy: Arc::new(wait_strategy),
-            gating_sequences: Vec::new(),
+            gating_sequences: Default::default(),
             buffer_size,
             is_done: Default::default(),
+            invalid_slots: Arc::new(InvalidSlots::new(buffer_size)),
+            wakers: Default::default(),
+            capacity_waits: Default::default(),
         }
     }
 }
 
+impl<W: WaitStrategy> SingleProducerSequencer<W> {
+    fn min_gating_sequence(&self) -> Sequence {
+        min_cursor_sequence(self.gating_sequences.load())
+    }
+}
+
 impl<W: WaitStrategy> Sequencer for SingleProducerSequencer<W> {
     type Barrier = ProcessingSequenceBarrier<W>;
 
-    fn next(&self, count: usize) -> (Sequence, Sequence) {
+    fn next(&self, count: usize) -> Result<(Sequence, Sequence), HaltedError> {
+        if count == 0 {
+            let next = self.next_write_sequence.get();
+            return Ok((next, next - 1));
+        }
+
         let mut min_sequence = self.cached_available_sequence.take();
         let next = self.next_write_sequence.take();
         let (start, end) = (next, next + (count - 1) as Sequence);
 
+        if min_sequence + (self.buffer_size as Sequence) < end {
+            min_sequence = self.min_gating_sequence();
+            if min_sequence + (self.buffer_size as Sequence) < end {
+                self.capacity_waits.fetch_add(1, Ordering::Relaxed);
+            }
+        }
         while min_sequence + (self.buffer_size as Sequence) < end {
-            min_sequence = min_cursor_sequence(&self.gating_sequences);
+            if self.is_done.load(Ordering::Relaxed) {
+                self.cached_available_sequence.set(min_sequence);
+                self.next_write_sequence.set(next);
+                return Err(HaltedError);
+            }
+            min_sequence = self.min_gating_sequence();
         }
 
         self.cached_available_sequence.set(min_sequence);
         self.next_write_sequence.set(end + 1);
 
-        (start, end)
+        Ok((start, end))
+    }
+
+    fn try_next(&self, count: usize) -> Result<(Sequence, Sequence), InsufficientCapacity> {
+        let next = self.next_write_sequence.get();
+        if count == 0 {
+            return Ok((next, next - 1));
+        }
+
+        let (start, end) = (next, next + (count - 1) as Sequence);
+
+        let mut min_sequence = self.cached_available_sequence.get();
+        if min_sequence + (self.buffer_size as Sequence) < end {
+            min_sequence = self.min_gating_sequence();
+            self.cached_available_sequence.set(min_sequence);
+            if min_sequence + (self.buffer_size as Sequence) < end {
+                return Err(InsufficientCapacity);
+            }
+        }
+
+        self.next_write_sequence.set(end + 1);
+
+        Ok((start, end))
     }
 
-    fn publish(&self, _: Sequence, hi: Sequence) {
+    fn publish(&self, lo: Sequence, hi: Sequence) {
+        if hi < lo {
+            return;
+        }
+
+        let mut pending = self.pending_ranges.borrow_mut();
+        if lo != self.cursor.get() + 1 {
+            pending.insert(lo, hi);
+            return;
+        }
+
+        let mut hi = hi;
+        while let Some(end) = pending.remove(&(hi + 1)) {
+            hi = end;
+        }
+        drop(pending);
+
         self.cursor.set(hi);
         self.wait_strategy.signal();
+        self.wakers.wake_all();
+    }
+
+    fn invalidate(&self, lo: Sequence, hi: Sequence) {
+        self.invalid_slots.invalidate(lo, hi);
     }
 
     fn create_barrier(
-        &mut self,
+        &self,
         gating_sequences: &[Arc<AtomicSequence>],
     ) -> ProcessingSequenceBarrier<W> {
         ProcessingSequenceBarrier::new(
             self.wait_strategy.clone(),
             Vec::from(gating_sequences),
             self.is_done.clone(),
+            self.invalid_slots.clone(),
+            self.wakers.clone(),
         )
     }
 
-    fn add_gating_sequence(&mut self, gating_sequence: &Arc<AtomicSequence>) {
-        self.gating_sequences.push(gating_sequence.clone());
+    fn add_gating_sequence(&self, gating_sequence: &Arc<AtomicSequence>) {
+        self.gating_sequences.add(gating_sequence, &self.cursor);
+    }
+
+    fn remove_gating_sequence(&self, gating_sequence: &Arc<AtomicSequence>) -> bool {
+        self.gating_sequences.remove(gating_sequence)
+    }
+
+    fn gating_sequences(&self) -> Arc<GatingSequences> {
+        self.gating_sequences.clone()
     }
 
     fn get_cursor(&self) -> Arc<AtomicSequence> {
         self.cursor.clone()
     }
 
+    fn stats(&self) -> SequencerStats {
+        let claimed = self.next_write_sequence.get() - 1;
+        sequencer_stats(
+            self.cursor.get(),
+            claimed,
+            self.gating_sequences.load(),
+            self.buffer_size,
+            self.capacity_waits.load(Ordering::Relaxed),
+        )
+    }
+
+    #[cfg(feature = "async")]
+    fn poll_next(
+        &self,
+        count: usize,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(Sequence, Sequence), HaltedError>> {
+        if let Ok(range) = self.try_next(count) {
+            return Poll::Ready(Ok(range));
+        }
+
+        self.wakers.register(cx.waker());
+        match self.try_next(count) {
+            Ok(range) => Poll::Ready(Ok(range)),
+            Err(_) if self.is_done.load(Ordering::SeqCst) => Poll::Ready(Err(HaltedError)),
+            Err(_) => Poll::Pending,
+        }
+    }
+
+    fn halt(&self) {
+        self.is_done.store(true, Ordering::SeqCst);
+        self.wait_strategy.signal();
+        self.wakers.wake_all();
+    }
+
     fn drain(self) {
         let current = self.next_write_sequence.take() - 1;
-        while min_cursor_sequence(&self.gating_sequences) < current {
+        while self.min_gating_sequence() < current && !self.is_done.load(Ordering::SeqCst) {
             self.wait_strategy.signal();
         }
         self.is_done.store(true, Ordering::SeqCst);
@@ -89,15 +225,14 @@ impl<W: WaitStrategy> Sequencer for SingleProducerSequencer<W> {
 
 impl<W: WaitStrategy> Drop for SingleProducerSequencer<W> {
     fn drop(&mut self) {
-        self.is_done.store(true, Ordering::SeqCst);
-        self.wait_strategy.signal();
+        self.halt();
     }
 }
 
 impl<'a, D: DataProvider<T> + 'a, T, S: Sequencer + 'a> EventProducer<'a> for Producer<D, T, S> {
     type Item = T;
 
-    fn write<F, U, I, E>(&self, items: I, f: F)
+    fn write<F, U, I, E>(&self, items: I, f: F) -> Result<(), HaltedError>
     where
         D: DataProvider<T>,
         I: IntoIterator<Item = U, IntoIter = E>,
@@ -105,13 +240,24 @@ impl<'a, D: DataProvider<T> + 'a, T, S: Sequencer + 'a> EventProducer<'a> for Pr
         F: Fn(&mut Self::Item, Sequence, &U),
     {
         let iter = items.into_iter();
-        let (start, end) = self.sequencer.next(iter.len());
-        for (idx, item) in iter.enumerate() {
-            let seq = start + idx as Sequence;
-            let slot = unsafe { self.data_provider.get_mut(seq) };
-            f(slot, seq, &item);
-        }
+        let (start, end) = self.sequencer.next(iter.len())?;
+        self.fill(start, iter, f);
         self.sequencer.publish(start, end);
+        Ok(())
+    }
+
+    fn try_write<F, U, I, E>(&self, items: I, f: F) -> Result<(), InsufficientCapacity>
+    where
+        D: DataProvider<T>,
+        I: IntoIterator<Item = U, IntoIter = E>,
+        E: ExactSizeIterator<Item = U>,
+        F: Fn(&mut Self::Item, Sequence, &U),
+    {
+        let iter = items.into_iter();
+        let (start, end) = self.sequencer.try_next(iter.len())?;
+        self.fill(start, iter, f);
+        self.sequencer.publish(start, end);
+        Ok(())
     }
 
     fn drain(self) {
@@ -127,111 +273,477 @@ impl<D: DataProvider<T>, T, S: Sequencer> Producer<D, T, S> {
             _element: Default::default(),
         }
     }
+
+    pub(crate) fn sequencer(&self) -> &S {
+        &self.sequencer
+    }
+
+    pub(crate) fn data_provider(&self) -> Arc<D> {
+        self.data_provider.clone()
+    }
+
+    pub fn claim(&self, count: usize) -> Result<EventClaim<'_, D, T, S>, HaltedError> {
+        assert!(
+            count <= self.data_provider.buffer_size(),
+            "claim exceeds ring buffer capacity"
+        );
+        let (start, end) = self.sequencer.next(count)?;
+        Ok(EventClaim {
+            producer: self,
+            start,
+            end,
+        })
+    }
+
+    pub fn try_claim(&self, count: usize) -> Result<EventClaim<'_, D, T, S>, InsufficientCapacity> {
+        let (start, end) = self.sequencer.try_next(count)?;
+        Ok(EventClaim {
+            producer: self,
+            start,
+            end,
+        })
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn claim_async(&self, count: usize) -> Result<EventClaim<'_, D, T, S>, HaltedError> {
+        assert!(
+            count <= self.data_provider.buffer_size(),
+            "claim exceeds ring buffer capacity"
+        );
+        let (start, end) = NextFuture {
+            sequencer: &self.sequencer,
+            count,
+        }
+        .await?;
+        Ok(EventClaim {
+            producer: self,
+            start,
+            end,
+        })
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn write_async<F, U, I, E>(&self, items: I, f: F) -> Result<(), HaltedError>
+    where
+        I: IntoIterator<Item = U, IntoIter = E>,
+        E: ExactSizeIterator<Item = U>,
+        F: Fn(&mut T, Sequence, &U),
+    {
+        let iter = items.into_iter();
+        let claim = self.claim_async(iter.len()).await?;
+        self.fill(claim.start(), iter, f);
+        Ok(())
+    }
+
+    pub fn publish_event<A, X: Translator<T, A>>(
+        &self,
+        translator: &X,
+        args: A,
+    ) -> Result<(), HaltedError> {
+        let mut claim = self.claim(1)?;
+        let sequence = claim.start();
+        translator.translate(&mut claim[0], sequence, args);
+        Ok(())
+    }
+
+    pub fn try_publish_event<A, X: Translator<T, A>>(
+        &self,
+        translator: &X,
+        args: A,
+    ) -> Result<(), InsufficientCapacity> {
+        let mut claim = self.try_claim(1)?;
+        let sequence = claim.start();
+        translator.translate(&mut claim[0], sequence, args);
+        Ok(())
+    }
+
+    pub fn publish_events<A, X, I, E>(&self, translator: &X, args: I) -> Result<(), HaltedError>
+    where
+        X: Translator<T, A>,
+        I: IntoIterator<Item = A, IntoIter = E>,
+        E: ExactSizeIterator<Item = A>,
+    {
+        let iter = args.into_iter();
+        if iter.len() == 0 {
+            return Ok(());
+        }
+
+        let mut claim = self.claim(iter.len())?;
+        let start = claim.start();
+        for (idx, (slot, args)) in claim.iter_mut().zip(iter).enumerate() {
+            translator.translate(slot, start + idx as Sequence, args);
+        }
+        Ok(())
+    }
+
+    fn fill<F, U, E>(&self, start: Sequence, iter: E, f: F)
+    where
+        E: Iterator<Item = U>,
+        F: Fn(&mut T, Sequence, &U),
+    {
+        for (idx, item) in iter.enumerate() {
+            let seq = start + idx as Sequence;
+            let slot = unsafe { self.data_provider.get_mut(seq) };
+            f(slot, seq, &item);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+struct NextFuture<'s, S: Sequencer> {
+    sequencer: &'s S,
+    count: usize,
+}
+
+#[cfg(feature = "async")]
+impl<'s, S: Sequencer> Future for NextFuture<'s, S> {
+    type Output = Result<(Sequence, Sequence), HaltedError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.sequencer.poll_next(self.count, cx)
+    }
+}
+
+pub struct EventClaim<'p, D: DataProvider<T>, T, S: Sequencer> {
+    producer: &'p Producer<D, T, S>,
+    start: Sequence,
+    end: Sequence,
+}
+
+impl<'p, D: DataProvider<T>, T, S: Sequencer> EventClaim<'p, D, T, S> {
+    pub fn start(&self) -> Sequence {
+        self.start
+    }
+
+    pub fn end(&self) -> Sequence {
+        self.end
+    }
+
+    pub fn len(&self) -> usize {
+        (self.end - self.start + 1) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        let data_provider = &self.producer.data_provider;
+        (self.start..=self.end).map(move |seq| unsafe { data_provider.get_mut(seq) })
+    }
+
+    pub fn commit(self) {}
+
+    fn sequence_at(&self, index: usize) -> Sequence {
+        assert!(index < self.len(), "index out of claimed range");
+        self.start + index as Sequence
+    }
+}
+
+impl<'p, D: DataProvider<T>, T, S: Sequencer> Index<usize> for EventClaim<'p, D, T, S> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        unsafe { self.producer.data_provider.get(self.sequence_at(index)) }
+    }
+}
+
+impl<'p, D: DataProvider<T>, T, S: Sequencer> IndexMut<usize> for EventClaim<'p, D, T, S> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        unsafe { self.producer.data_provider.get_mut(self.sequence_at(index)) }
+    }
+}
+
+impl<'p, D: DataProvider<T>, T, S: Sequencer> Drop for EventClaim<'p, D, T, S> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.producer.sequencer.invalidate(self.start, self.end);
+        }
+        self.producer.sequencer.publish(self.start, self.end);
+    }
 }
 
 // --------------------------------------------------------------
 
 pub struct MultiProducerSequencer<W: WaitStrategy> {
+    inner: Arc<MultiProducerState<W>>,
+}
+
+struct MultiProducerState<W: WaitStrategy> {
     cursor: Arc<AtomicSequence>,
     wait_strategy: Arc<W>,
-    gating_sequences: Vec<Arc<AtomicSequence>>,
+    gating_sequences: Arc<GatingSequences>,
     buffer_size: usize,
+    index_shift: u32,
     high_watermark: AtomicSequence,
     ready_sequences: BitMap,
     is_done: Arc<AtomicBool>,
+    invalid_slots: Arc<InvalidSlots>,
+    wakers: Arc<WakerSet>,
+    handles: AtomicUsize,
+    capacity_waits: AtomicU64,
 }
 
 impl<W: WaitStrategy> MultiProducerSequencer<W> {
     pub fn new(buffer_size: usize, wait_strategy: W) -> Self {
         MultiProducerSequencer {
-            cursor: Arc::new(AtomicSequence::default()),
-            wait_strategy: Arc::new(wait_strategy),
-            gating_sequences: Vec::new(),
-            buffer_size,
-            high_watermark: AtomicSequence::default(),
-            ready_sequences: BitMap::new(buffer_size),
-            is_done: Default::default(),
+            inner: Arc::new(MultiProducerState {
+                cursor: Arc::new(AtomicSequence::default()),
+                wait_strategy: Arc::new(wait_strategy),
+                gating_sequences: Default::default(),
+                buffer_size,
+                index_shift: buffer_size.trailing_zeros(),
+                high_watermark: AtomicSequence::default(),
+                ready_sequences: BitMap::new(buffer_size),
+                is_done: Default::default(),
+                invalid_slots: Arc::new(InvalidSlots::new(buffer_size)),
+                wakers: Default::default(),
+                handles: AtomicUsize::new(1),
+                capacity_waits: Default::default(),
+            }),
         }
     }
+}
+
+impl<W: WaitStrategy> MultiProducerState<W> {
+    fn min_gating_sequence(&self) -> Sequence {
+        min_cursor_sequence(self.gating_sequences.load())
+    }
 
     fn has_capacity(&self, high_watermark: Sequence, count: usize) -> bool {
-        self.buffer_size
-            > (high_watermark - min_cursor_sequence(&self.gating_sequences)) as usize + count
+        let in_flight = high_watermark - self.min_gating_sequence();
+        in_flight + (count as Sequence) <= self.buffer_size as Sequence
+    }
+
+    fn is_even_round(&self, sequence: Sequence) -> bool {
+        (sequence >> self.index_shift) & 1 == 0
+    }
+
+    fn set_published(&self, sequence: Sequence) {
+        if self.is_even_round(sequence) {
+            self.ready_sequences.set(sequence);
+        } else {
+            self.ready_sequences.unset(sequence);
+        }
+    }
+
+    fn is_published(&self, sequence: Sequence) -> bool {
+        self.ready_sequences.is_set(sequence) == self.is_even_round(sequence)
+    }
+}
+
+impl<W: WaitStrategy> Clone for MultiProducerSequencer<W> {
+    fn clone(&self) -> Self {
+        self.inner.handles.fetch_add(1, Ordering::SeqCst);
+        MultiProducerSequencer {
+            inner: self.inner.clone(),
+        }
     }
 }
 
 impl<W: WaitStrategy> Sequencer for MultiProducerSequencer<W> {
     type Barrier = ProcessingSequenceBarrier<W>;
 
-    fn next(&self, count: usize) -> (Sequence, Sequence) {
+    fn next(&self, count: usize) -> Result<(Sequence, Sequence), HaltedError> {
+        let inner = &self.inner;
+        let mut waited = false;
         loop {
-            let high_watermark = self.high_watermark.get();
-            if self.has_capacity(high_watermark, count) {
+            let high_watermark = inner.high_watermark.get();
+            if inner.has_capacity(high_watermark, count) {
                 let end = high_watermark + count as Sequence;
-                if self.high_watermark.compare_exchange(high_watermark, end) {
-                    return (high_watermark + 1, end);
+                if inner.high_watermark.compare_exchange(high_watermark, end) {
+                    return Ok((high_watermark + 1, end));
                 }
+            } else if inner.is_done.load(Ordering::Relaxed) {
+                return Err(HaltedError);
+            } else if !waited {
+                waited = true;
+                inner.capacity_waits.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn try_next(&self, count: usize) -> Result<(Sequence, Sequence), InsufficientCapacity> {
+        let inner = &self.inner;
+        loop {
+            let high_watermark = inner.high_watermark.get();
+            if !inner.has_capacity(high_watermark, count) {
+                return Err(InsufficientCapacity);
+            }
+
+            let end = high_watermark + count as Sequence;
+            if inner.high_watermark.compare_exchange(high_watermark, end) {
+                return Ok((high_watermark + 1, end));
             }
         }
     }
 
     fn publish(&self, lo: Sequence, hi: Sequence) {
+        if hi < lo {
+            return;
+        }
+
+        let inner = &self.inner;
         for n in lo..=hi {
-            self.ready_sequences.set(n);
+            inner.set_published(n);
         }
 
-        let low_watermark = self.cursor.get() + 1;
-        let mut good_to_release = low_watermark - 1;
-        for n in low_watermark..=self.high_watermark.get() {
-            if self.ready_sequences.is_set(n) {
-                good_to_release = n;
-            } else {
-                break;
+        let mut current = inner.cursor.get();
+        loop {
+            let high_watermark = inner.high_watermark.get();
+            let mut good_to_release = current;
+            while good_to_release < high_watermark && inner.is_published(good_to_release + 1) {
+                good_to_release += 1;
             }
-        }
 
-        if good_to_release > low_watermark {
-            for n in low_watermark..=good_to_release {
-                self.ready_sequences.unset(n);
+            if good_to_release == current {
+                break;
             }
 
-            let mut current = low_watermark;
-            while !self.cursor.compare_exchange(current, good_to_release) {
-                current = self.cursor.get();
-                if current > good_to_release {
-                    break;
-                }
+            if inner.cursor.compare_exchange(current, good_to_release) {
+                current = good_to_release;
+            } else {
+                current = inner.cursor.get();
             }
         }
 
-        self.wait_strategy.signal();
+        inner.wait_strategy.signal();
+        inner.wakers.wake_all();
+    }
+
+    fn invalidate(&self, lo: Sequence, hi: Sequence) {
+        self.inner.invalid_slots.invalidate(lo, hi);
     }
 
     fn create_barrier(
-        &mut self,
+        &self,
         gating_sequences: &[Arc<AtomicSequence>],
     ) -> ProcessingSequenceBarrier<W> {
         ProcessingSequenceBarrier::new(
-            self.wait_strategy.clone(),
+            self.inner.wait_strategy.clone(),
             Vec::from(gating_sequences),
-            self.is_done.clone(),
+            self.inner.is_done.clone(),
+            self.inner.invalid_slots.clone(),
+            self.inner.wakers.clone(),
         )
     }
 
-    fn add_gating_sequence(&mut self, gating_sequence: &Arc<AtomicSequence>) {
-        self.gating_sequences.push(gating_sequence.clone());
+    fn add_gating_sequence(&self, gating_sequence: &Arc<AtomicSequence>) {
+        let inner = &self.inner;
+        inner.gating_sequences.add(gating_sequence, &inner.cursor);
+    }
+
+    fn remove_gating_sequence(&self, gating_sequence: &Arc<AtomicSequence>) -> bool {
+        self.inner.gating_sequences.remove(gating_sequence)
+    }
+
+    fn gating_sequences(&self) -> Arc<GatingSequences> {
+        self.inner.gating_sequences.clone()
     }
 
     fn get_cursor(&self) -> Arc<AtomicSequence> {
-        self.cursor.clone()
+        self.inner.cursor.clone()
+    }
+
+    fn stats(&self) -> SequencerStats {
+        let inner = &self.inner;
+        sequencer_stats(
+            inner.cursor.get(),
+            inner.high_watermark.get(),
+            inner.gating_sequences.load(),
+            inner.buffer_size,
+            inner.capacity_waits.load(Ordering::Relaxed),
+        )
     }
 
+    #[cfg(feature = "async")]
+    fn poll_next(
+        &self,
+        count: usize,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(Sequence, Sequence), HaltedError>> {
+        if let Ok(range) = self.try_next(count) {
+            return Poll::Ready(Ok(range));
+        }
+
+        self.inner.wakers.register(cx.waker());
+        match self.try_next(count) {
+            Ok(range) => Poll::Ready(Ok(range)),
+            Err(_) if self.inner.is_done.load(Ordering::SeqCst) => Poll::Ready(Err(HaltedError)),
+            Err(_) => Poll::Pending,
+        }
+    }
+
+    fn halt(&self) {
+        self.inner.is_done.store(true, Ordering::SeqCst);
+        self.inner.wait_strategy.signal();
+        self.inner.wakers.wake_all();
+    }
+
+    // Clones share one sequencer: draining any clone waits for everything
+    // claimed so far to be consumed and then halts all of them, while dropping
+    // the last clone halts without waiting.
     fn drain(self) {
-        let current = self.cursor.get();
-        while min_cursor_sequence(&self.gating_sequences) < current {
-            self.wait_strategy.signal();
+        let inner = &self.inner;
+        let current = inner.high_watermark.get();
+        while !inner.gating_sequences.load().is_empty()
+            && inner.min_gating_sequence() < current
+            && !inner.is_done.load(Ordering::SeqCst)
+        {
+            inner.wait_strategy.signal();
         }
-        self.is_done.store(true, Ordering::SeqCst);
-        self.wait_strategy.signal();
+        self.halt();
+    }
+}
+
+impl<W: WaitStrategy> Drop for MultiProducerSequencer<W> {
+    fn drop(&mut self) {
+        if self.inner.handles.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.halt();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::wait::SpinLoopWaitStrategy;
+
+    #[test]
+    fn capacity_check_tolerates_stale_watermark() {
+        let sequencer = MultiProducerSequencer::new(8, SpinLoopWaitStrategy::new());
+        let gating = Arc::new(AtomicSequence::default());
+        sequencer.add_gating_sequence(&gating);
+
+        for _ in 0..3 {
+            let (lo, hi) = sequencer.next(8).unwrap();
+            sequencer.publish(lo, hi);
+            gating.set(hi);
+        }
+
+        let inner = &sequencer.inner;
+        assert!(inner.has_capacity(15, 8));
+        assert!(inner.has_capacity(23, 8));
+        assert!(!inner.has_capacity(23, 9));
+        assert_eq!(sequencer.try_next(8), Ok((24, 31)));
+        assert_eq!(sequencer.try_next(1), Err(InsufficientCapacity));
+    }
+
+    #[test]
+    fn gating_snapshot_survives_detach() {
+        let sequencer = MultiProducerSequencer::new(8, SpinLoopWaitStrategy::new());
+        let first = Arc::new(AtomicSequence::default());
+        let second = Arc::new(AtomicSequence::default());
+        sequencer.add_gating_sequence(&first);
+        sequencer.add_gating_sequence(&second);
+
+        let gating = sequencer.gating_sequences();
+        let snapshot = gating.load();
+        assert!(sequencer.remove_gating_sequence(&first));
+        assert!(!sequencer.remove_gating_sequence(&first));
+
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(gating.load().len(), 1);
+        assert!(Arc::ptr_eq(&gating.load()[0], &second));
     }
 }
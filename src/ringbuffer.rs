@@ -9,14 +9,20 @@ pub struct RingBuffer<T> {
 
 impl<T: Default> RingBuffer<T> {
     pub fn new(capacity: usize) -> Self {
+        Self::with_factory(capacity, |_| T::default())
+    }
+}
+
+impl<T> RingBuffer<T> {
+    pub fn with_factory<F: FnMut(Sequence) -> T>(capacity: usize, mut factory: F) -> Self {
         assert!(
             (capacity != 0) && ((capacity & (capacity - 1)) == 0),
             "capacity must be power of two"
         );
 
         let mut data = Vec::with_capacity(capacity);
-        for _ in 0..capacity {
-            data.push(UnsafeCell::default());
+        for i in 0..capacity {
+            data.push(UnsafeCell::new(factory(i as Sequence)));
         }
 
         RingBuffer {
@@ -108,4 +114,15 @@ mod test {
         t2.thread().unpark();
         t2.join().unwrap();
     }
+
+    #[test]
+    fn populates_slots_from_factory() {
+        let buffer = RingBuffer::with_factory(8, |seq| Vec::<u8>::with_capacity(seq as usize + 1));
+        for i in 0..8 {
+            unsafe {
+                assert!(buffer.get(i).is_empty());
+                assert!(buffer.get(i).capacity() > i as usize);
+            }
+        }
+    }
 }
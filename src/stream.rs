@@ -0,0 +1,111 @@
+use crate::prelude::*;
+use futures_core::Stream;
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+pub struct EventStream<B, D, T, U, F>
+where
+    B: SequenceBarrier,
+    D: DataProvider<T>,
+    F: FnMut(&T, Sequence) -> U,
+{
+    barrier: B,
+    data_provider: Arc<D>,
+    cursor: Arc<AtomicSequence>,
+    available: Sequence,
+    map: F,
+    _element: PhantomData<fn(&T) -> U>,
+}
+
+impl<B, D, T> EventStream<B, D, T, T, fn(&T, Sequence) -> T>
+where
+    B: SequenceBarrier,
+    D: DataProvider<T>,
+    T: Clone,
+{
+    pub fn new(barrier: B, data_provider: Arc<D>) -> Self {
+        Self::with_mapper(barrier, data_provider, |event, _| event.clone())
+    }
+}
+
+impl<B, D, T, U, F> EventStream<B, D, T, U, F>
+where
+    B: SequenceBarrier,
+    D: DataProvider<T>,
+    F: FnMut(&T, Sequence) -> U,
+{
+    pub fn with_mapper(barrier: B, data_provider: Arc<D>, map: F) -> Self {
+        EventStream {
+            barrier,
+            data_provider,
+            cursor: Default::default(),
+            available: -1,
+            map,
+            _element: Default::default(),
+        }
+    }
+
+    pub fn get_cursor(&self) -> Arc<AtomicSequence> {
+        self.cursor.clone()
+    }
+
+    fn has_available(&mut self, next: Sequence) -> bool {
+        if self.available < next {
+            self.available = self.barrier.available();
+        }
+        self.available >= next
+    }
+}
+
+impl<B, D, T, U, F> Unpin for EventStream<B, D, T, U, F>
+where
+    B: SequenceBarrier,
+    D: DataProvider<T>,
+    F: FnMut(&T, Sequence) -> U,
+{
+}
+
+impl<B, D, T, U, F> Stream for EventStream<B, D, T, U, F>
+where
+    B: SequenceBarrier,
+    D: DataProvider<T>,
+    F: FnMut(&T, Sequence) -> U,
+{
+    type Item = U;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<U>> {
+        let this = self.get_mut();
+        loop {
+            if this.barrier.is_alerted() {
+                return Poll::Ready(None);
+            }
+
+            let next = this.cursor.get() + 1;
+            if !this.has_available(next) {
+                this.barrier.register_waker(cx.waker());
+                if !this.has_available(next) {
+                    if this.barrier.is_alerted() {
+                        return Poll::Ready(None);
+                    }
+                    return Poll::Pending;
+                }
+            }
+
+            if this.barrier.is_invalid(next) {
+                this.cursor.set(next);
+                this.barrier.signal();
+                continue;
+            }
+
+            let event = unsafe { this.data_provider.get(next) };
+            let item = (this.map)(event, next);
+            this.cursor.set(next);
+            this.barrier.signal();
+            return Poll::Ready(Some(item));
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::prelude::*;
+use log::*;
+
+#[derive(Debug, Clone, Default)]
+pub struct ThreadConfig {
+    name: Option<String>,
+    label: Option<String>,
+    stack_size: Option<usize>,
+    affinity: Option<Vec<usize>>,
+    priority: Option<i32>,
+}
+
+impl ThreadConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn name<N: Into<String>>(mut self, name: N) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn stack_size(mut self, stack_size: usize) -> Self {
+        self.stack_size = Some(stack_size);
+        self
+    }
+
+    pub fn affinity<I: IntoIterator<Item = usize>>(mut self, cores: I) -> Self {
+        self.affinity = Some(cores.into_iter().collect());
+        self
+    }
+
+    pub fn priority(mut self, nice: i32) -> Self {
+        self.priority = Some(nice);
+        self
+    }
+
+    pub(crate) fn indexed(&self, index: usize) -> Self {
+        let mut config = self.clone();
+        config.name = self.name.as_ref().map(|name| format!("{}-{}", name, index));
+        config
+    }
+
+    pub(crate) fn labeled(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub(crate) fn builder(&self) -> std::thread::Builder {
+        let mut builder = std::thread::Builder::new();
+        if let Some(name) = &self.name {
+            builder = builder.name(name.clone());
+        }
+        if let Some(stack_size) = self.stack_size {
+            builder = builder.stack_size(stack_size);
+        }
+        builder
+    }
+
+    pub(crate) fn thread_name(&self, runnable: &dyn Runnable) -> String {
+        self.name
+            .clone()
+            .or_else(|| self.label.clone())
+            .unwrap_or_else(|| runnable.name())
+    }
+
+    pub(crate) fn apply(&self, name: &str) {
+        if let Some(cores) = &self.affinity {
+            if let Err(e) = sys::set_affinity(cores) {
+                warn!("failed to pin {} to cores {:?}: {}", name, cores, e);
+            }
+        }
+        if let Some(nice) = self.priority {
+            if let Err(e) = sys::set_priority(nice) {
+                warn!("failed to set priority {} for {}: {}", nice, name, e);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::io;
+    use std::os::raw::{c_int, c_uint, c_ulong};
+
+    const CPU_SETSIZE: usize = 1024;
+    const BITS: usize = 8 * std::mem::size_of::<c_ulong>();
+    const PRIO_PROCESS: c_int = 0;
+    const EINVAL: i32 = 22;
+
+    #[repr(C)]
+    struct CpuSet {
+        bits: [c_ulong; CPU_SETSIZE / BITS],
+    }
+
+    extern "C" {
+        fn sched_setaffinity(pid: c_int, cpusetsize: usize, mask: *const CpuSet) -> c_int;
+        fn setpriority(which: c_int, who: c_uint, prio: c_int) -> c_int;
+    }
+
+    pub fn set_affinity(cores: &[usize]) -> io::Result<()> {
+        let mut set = CpuSet {
+            bits: [0; CPU_SETSIZE / BITS],
+        };
+        for &core in cores.iter().filter(|&&core| core < CPU_SETSIZE) {
+            set.bits[core / BITS] |= 1 << (core % BITS);
+        }
+        if set.bits.iter().all(|&bits| bits == 0) {
+            return Err(io::Error::from_raw_os_error(EINVAL));
+        }
+
+        match unsafe { sched_setaffinity(0, std::mem::size_of::<CpuSet>(), &set) } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    pub fn set_priority(nice: i32) -> io::Result<()> {
+        match unsafe { setpriority(PRIO_PROCESS, 0, nice) } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::io;
+
+    pub fn set_affinity(_cores: &[usize]) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub fn set_priority(_nice: i32) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rejects_empty_affinity_mask() {
+        assert!(sys::set_affinity(&[]).is_err());
+        assert!(sys::set_affinity(&[4096]).is_err());
+    }
+}
@@ -1,8 +1,12 @@
 use crate::prelude::*;
 use std::{
+    any::Any,
     borrow::Borrow,
     iter::*,
-    sync::atomic::{AtomicU64, Ordering},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 
 pub fn min_cursor_sequence<S: Borrow<AtomicSequence>>(sequences: &[S]) -> Sequence {
@@ -13,6 +17,121 @@ pub fn min_cursor_sequence<S: Borrow<AtomicSequence>>(sequences: &[S]) -> Sequen
         .unwrap_or_default()
 }
 
+pub fn sequencer_stats(
+    cursor: Sequence,
+    claimed: Sequence,
+    gating_sequences: &[Arc<AtomicSequence>],
+    buffer_size: usize,
+    capacity_waits: u64,
+) -> SequencerStats {
+    let gating_sequences: Vec<_> = gating_sequences.iter().map(|s| s.get()).collect();
+    let in_flight = gating_sequences.iter().min().map_or(0, |min| claimed - min);
+    let remaining = (buffer_size as Sequence - in_flight).clamp(0, buffer_size as Sequence);
+    SequencerStats {
+        cursor,
+        gating_sequences,
+        buffer_size,
+        remaining_capacity: remaining as usize,
+        capacity_waits,
+    }
+}
+
+pub fn panic_message(error: &(dyn Any + Send)) -> &str {
+    if let Some(message) = error.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = error.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+pub fn short_type_name<E>() -> String {
+    let name = std::any::type_name::<E>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name).to_string()
+}
+
+pub struct HaltOnPanic<'b, B: SequenceBarrier> {
+    barrier: &'b B,
+}
+
+impl<'b, B: SequenceBarrier> HaltOnPanic<'b, B> {
+    pub fn new(barrier: &'b B) -> Self {
+        Self { barrier }
+    }
+}
+
+impl<'b, B: SequenceBarrier> Drop for HaltOnPanic<'b, B> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.barrier.halt();
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct WakerSet {
+    #[cfg(feature = "async")]
+    registered: std::sync::atomic::AtomicBool,
+    #[cfg(feature = "async")]
+    wakers: std::sync::Mutex<Vec<std::task::Waker>>,
+}
+
+impl WakerSet {
+    #[cfg(feature = "async")]
+    pub fn register(&self, waker: &std::task::Waker) {
+        let mut wakers = self.wakers.lock().unwrap();
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+        self.registered.store(true, Ordering::SeqCst);
+        std::sync::atomic::fence(Ordering::SeqCst);
+    }
+
+    pub fn wake_all(&self) {
+        #[cfg(feature = "async")]
+        {
+            std::sync::atomic::fence(Ordering::SeqCst);
+            if self.registered.load(Ordering::SeqCst)
+                && self.registered.swap(false, Ordering::SeqCst)
+            {
+                let wakers = std::mem::take(&mut *self.wakers.lock().unwrap());
+                for waker in wakers {
+                    waker.wake();
+                }
+            }
+        }
+    }
+}
+
+pub struct InvalidSlots {
+    slots: Vec<AtomicSequence>,
+    mask: i64,
+}
+
+impl InvalidSlots {
+    pub fn new(capacity: usize) -> Self {
+        let slots = Vec::from_iter(repeat_with(AtomicSequence::default).take(capacity));
+        Self {
+            slots,
+            mask: (capacity - 1) as i64,
+        }
+    }
+
+    pub fn is_invalid(&self, sequence: Sequence) -> bool {
+        let slot = unsafe { self.slots.get_unchecked((sequence & self.mask) as usize) };
+        slot.get() == sequence
+    }
+
+    pub fn invalidate(&self, lo: Sequence, hi: Sequence) {
+        for sequence in lo..=hi {
+            let slot = unsafe { self.slots.get_unchecked((sequence & self.mask) as usize) };
+            slot.set(sequence);
+        }
+    }
+}
+
 pub struct BitMap {
     slots: Vec<AtomicU64>,
     index_mask: i64,
@@ -46,24 +165,24 @@ impl BitMap {
     }
 
     pub fn is_set(&self, sequence: Sequence) -> bool {
-        let index = (sequence & self.index_mask >> self.index_shift) as usize;
-        let slot = unsafe { self.slots.get_unchecked(index) };
-        let val = slot.load(Ordering::SeqCst);
-        val & (1 << (index & self.word_bits_mask)) != 0
+        let (slot, bit) = self.locate(sequence);
+        slot.load(Ordering::SeqCst) & bit != 0
     }
 
     pub fn set(&self, sequence: Sequence) {
-        let index = (sequence & self.index_mask >> self.index_shift) as usize;
-        let slot = unsafe { self.slots.get_unchecked(index) };
-        let val = 1 << (index & self.word_bits_mask);
-        slot.fetch_or(val, Ordering::SeqCst);
+        let (slot, bit) = self.locate(sequence);
+        slot.fetch_or(bit, Ordering::SeqCst);
     }
 
     pub fn unset(&self, sequence: Sequence) {
-        let index = (sequence & self.index_mask >> self.index_shift) as usize;
-        let slot = unsafe { self.slots.get_unchecked(index) };
-        let val = !(1 << (index & self.word_bits_mask));
-        slot.fetch_and(val, Ordering::SeqCst);
+        let (slot, bit) = self.locate(sequence);
+        slot.fetch_and(!bit, Ordering::SeqCst);
+    }
+
+    fn locate(&self, sequence: Sequence) -> (&AtomicU64, u64) {
+        let index = (sequence & self.index_mask) as usize;
+        let slot = unsafe { self.slots.get_unchecked(index >> self.index_shift) };
+        (slot, 1 << (index & self.word_bits_mask))
     }
 }
 
@@ -2,34 +2,79 @@ use crate::prelude::*;
 use crate::utils::*;
 use std::{
     borrow::Borrow,
-    sync::{Condvar, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Condvar, Mutex,
+    },
+    time::{Duration, Instant},
 };
 
+const DEFAULT_SPIN_TRIES: usize = 100;
+const DEFAULT_RETRIES: usize = 200;
+const DEFAULT_SLEEP_NANOS: u64 = 100;
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(1);
+const PHASED_SPIN_TRIES: usize = 10_000;
+
 pub struct SpinLoopWaitStrategy;
 
+pub struct YieldingWaitStrategy {
+    spin_tries: usize,
+}
+
+pub struct SleepingWaitStrategy {
+    retries: usize,
+    sleep_nanos: u64,
+}
+
 pub struct BlockingWaitStrategy {
     guard: Mutex<()>,
     cvar: Condvar,
 }
 
-impl WaitStrategy for SpinLoopWaitStrategy {
-    fn new() -> Self {
+pub struct LiteBlockingWaitStrategy {
+    guard: Mutex<()>,
+    cvar: Condvar,
+    signal_needed: AtomicBool,
+}
+
+pub struct TimeoutBlockingWaitStrategy {
+    guard: Mutex<()>,
+    cvar: Condvar,
+    timeout: Duration,
+}
+
+pub struct PhasedBackoffWaitStrategy<F: WaitStrategy> {
+    spin_timeout: Duration,
+    yield_timeout: Duration,
+    fallback: F,
+}
+
+impl SpinLoopWaitStrategy {
+    pub fn new() -> Self {
         SpinLoopWaitStrategy {}
     }
+}
+
+impl Default for SpinLoopWaitStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
+impl WaitStrategy for SpinLoopWaitStrategy {
     fn wait_for<F: Fn() -> bool, S: Borrow<AtomicSequence>>(
         &self,
         sequence: Sequence,
         dependencies: &[S],
         check_alert: F,
-    ) -> Option<Sequence> {
+    ) -> Result<Sequence, WaitError> {
         loop {
             let available = min_cursor_sequence(dependencies);
             if available >= sequence {
-                return Some(available);
+                return Ok(available);
             }
             if check_alert() {
-                return None;
+                return Err(WaitError::Alerted);
             }
         }
     }
@@ -37,29 +82,129 @@ impl WaitStrategy for SpinLoopWaitStrategy {
     fn signal(&self) {}
 }
 
-impl WaitStrategy for BlockingWaitStrategy {
-    fn new() -> Self {
+impl YieldingWaitStrategy {
+    pub fn new() -> Self {
+        Self::with_spin_tries(DEFAULT_SPIN_TRIES)
+    }
+
+    pub fn with_spin_tries(spin_tries: usize) -> Self {
+        Self { spin_tries }
+    }
+}
+
+impl Default for YieldingWaitStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaitStrategy for YieldingWaitStrategy {
+    fn wait_for<F: Fn() -> bool, S: Borrow<AtomicSequence>>(
+        &self,
+        sequence: Sequence,
+        dependencies: &[S],
+        check_alert: F,
+    ) -> Result<Sequence, WaitError> {
+        let mut counter = self.spin_tries;
+        loop {
+            let available = min_cursor_sequence(dependencies);
+            if available >= sequence {
+                return Ok(available);
+            }
+            if check_alert() {
+                return Err(WaitError::Alerted);
+            }
+            if counter == 0 {
+                std::thread::yield_now();
+            } else {
+                counter -= 1;
+            }
+        }
+    }
+
+    fn signal(&self) {}
+}
+
+impl SleepingWaitStrategy {
+    pub fn new() -> Self {
+        Self::with_retries(DEFAULT_RETRIES, DEFAULT_SLEEP_NANOS)
+    }
+
+    pub fn with_retries(retries: usize, sleep_nanos: u64) -> Self {
+        Self {
+            retries,
+            sleep_nanos,
+        }
+    }
+}
+
+impl Default for SleepingWaitStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaitStrategy for SleepingWaitStrategy {
+    fn wait_for<F: Fn() -> bool, S: Borrow<AtomicSequence>>(
+        &self,
+        sequence: Sequence,
+        dependencies: &[S],
+        check_alert: F,
+    ) -> Result<Sequence, WaitError> {
+        let mut counter = self.retries;
+        loop {
+            let available = min_cursor_sequence(dependencies);
+            if available >= sequence {
+                return Ok(available);
+            }
+            if check_alert() {
+                return Err(WaitError::Alerted);
+            }
+            if counter > DEFAULT_SPIN_TRIES {
+                counter -= 1;
+            } else if counter > 0 {
+                counter -= 1;
+                std::thread::yield_now();
+            } else {
+                std::thread::sleep(Duration::from_nanos(self.sleep_nanos));
+            }
+        }
+    }
+
+    fn signal(&self) {}
+}
+
+impl BlockingWaitStrategy {
+    pub fn new() -> Self {
         Self {
             cvar: Condvar::new(),
             guard: Mutex::new(()),
         }
     }
+}
+
+impl Default for BlockingWaitStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
+impl WaitStrategy for BlockingWaitStrategy {
     fn wait_for<F: Fn() -> bool, S: Borrow<AtomicSequence>>(
         &self,
         sequence: Sequence,
         dependencies: &[S],
         check_alert: F,
-    ) -> Option<Sequence> {
+    ) -> Result<Sequence, WaitError> {
         loop {
             let blocked = self.guard.lock().unwrap();
             if check_alert() {
-                return None;
+                return Err(WaitError::Alerted);
             }
 
             let available = min_cursor_sequence(dependencies);
             if available >= sequence {
-                return Some(available);
+                return Ok(available);
             } else {
                 let _guard = self.cvar.wait(blocked).unwrap();
             }
@@ -72,3 +217,178 @@ impl WaitStrategy for BlockingWaitStrategy {
         drop(_guard);
     }
 }
+
+impl LiteBlockingWaitStrategy {
+    pub fn new() -> Self {
+        Self {
+            cvar: Condvar::new(),
+            guard: Mutex::new(()),
+            signal_needed: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Default for LiteBlockingWaitStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaitStrategy for LiteBlockingWaitStrategy {
+    fn wait_for<F: Fn() -> bool, S: Borrow<AtomicSequence>>(
+        &self,
+        sequence: Sequence,
+        dependencies: &[S],
+        check_alert: F,
+    ) -> Result<Sequence, WaitError> {
+        let available = min_cursor_sequence(dependencies);
+        if available >= sequence {
+            return Ok(available);
+        }
+
+        let mut blocked = self.guard.lock().unwrap();
+        loop {
+            self.signal_needed.swap(true, Ordering::SeqCst);
+            if check_alert() {
+                return Err(WaitError::Alerted);
+            }
+
+            let available = min_cursor_sequence(dependencies);
+            if available >= sequence {
+                return Ok(available);
+            }
+            blocked = self.cvar.wait(blocked).unwrap();
+        }
+    }
+
+    fn signal(&self) {
+        if self.signal_needed.swap(false, Ordering::SeqCst) {
+            let _guard = self.guard.lock().unwrap();
+            self.cvar.notify_all();
+            drop(_guard);
+        }
+    }
+}
+
+impl TimeoutBlockingWaitStrategy {
+    pub fn new() -> Self {
+        Self::with_timeout(DEFAULT_TIMEOUT)
+    }
+
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            cvar: Condvar::new(),
+            guard: Mutex::new(()),
+            timeout,
+        }
+    }
+}
+
+impl Default for TimeoutBlockingWaitStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaitStrategy for TimeoutBlockingWaitStrategy {
+    fn wait_for<F: Fn() -> bool, S: Borrow<AtomicSequence>>(
+        &self,
+        sequence: Sequence,
+        dependencies: &[S],
+        check_alert: F,
+    ) -> Result<Sequence, WaitError> {
+        let deadline = Instant::now() + self.timeout;
+        let mut blocked = self.guard.lock().unwrap();
+        loop {
+            if check_alert() {
+                return Err(WaitError::Alerted);
+            }
+
+            let available = min_cursor_sequence(dependencies);
+            if available >= sequence {
+                return Ok(available);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(WaitError::Timeout);
+            }
+            blocked = self.cvar.wait_timeout(blocked, deadline - now).unwrap().0;
+        }
+    }
+
+    fn signal(&self) {
+        let _guard = self.guard.lock().unwrap();
+        self.cvar.notify_all();
+        drop(_guard);
+    }
+}
+
+impl<F: WaitStrategy> PhasedBackoffWaitStrategy<F> {
+    pub fn new(spin_timeout: Duration, yield_timeout: Duration, fallback: F) -> Self {
+        Self {
+            spin_timeout,
+            yield_timeout: spin_timeout + yield_timeout,
+            fallback,
+        }
+    }
+}
+
+impl PhasedBackoffWaitStrategy<BlockingWaitStrategy> {
+    pub fn with_lock(spin_timeout: Duration, yield_timeout: Duration) -> Self {
+        Self::new(spin_timeout, yield_timeout, BlockingWaitStrategy::new())
+    }
+}
+
+impl PhasedBackoffWaitStrategy<LiteBlockingWaitStrategy> {
+    pub fn with_lite_lock(spin_timeout: Duration, yield_timeout: Duration) -> Self {
+        Self::new(spin_timeout, yield_timeout, LiteBlockingWaitStrategy::new())
+    }
+}
+
+impl PhasedBackoffWaitStrategy<SleepingWaitStrategy> {
+    pub fn with_sleep(spin_timeout: Duration, yield_timeout: Duration) -> Self {
+        Self::new(spin_timeout, yield_timeout, SleepingWaitStrategy::new())
+    }
+}
+
+impl<W: WaitStrategy> WaitStrategy for PhasedBackoffWaitStrategy<W> {
+    fn wait_for<F: Fn() -> bool, S: Borrow<AtomicSequence>>(
+        &self,
+        sequence: Sequence,
+        dependencies: &[S],
+        check_alert: F,
+    ) -> Result<Sequence, WaitError> {
+        let mut start: Option<Instant> = None;
+        let mut counter = PHASED_SPIN_TRIES;
+        loop {
+            let available = min_cursor_sequence(dependencies);
+            if available >= sequence {
+                return Ok(available);
+            }
+            if check_alert() {
+                return Err(WaitError::Alerted);
+            }
+
+            counter -= 1;
+            if counter == 0 {
+                match start {
+                    None => start = Some(Instant::now()),
+                    Some(start) => {
+                        let elapsed = start.elapsed();
+                        if elapsed > self.yield_timeout {
+                            return self.fallback.wait_for(sequence, dependencies, check_alert);
+                        } else if elapsed > self.spin_timeout {
+                            std::thread::yield_now();
+                        }
+                    }
+                }
+                counter = PHASED_SPIN_TRIES;
+            }
+        }
+    }
+
+    fn signal(&self) {
+        self.fallback.signal();
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{prelude::*, utils::*};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+pub struct WorkerPool;
+
+impl WorkerPool {
+    pub fn create<'a, E, T, I>(handlers: I) -> Vec<impl EventProcessor<'a, T>>
+    where
+        T: Send + 'a,
+        E: WorkHandler<T> + Send + 'a,
+        I: IntoIterator<Item = E>,
+    {
+        let work_sequence: Arc<AtomicSequence> = Default::default();
+        handlers
+            .into_iter()
+            .map(|handler| WorkProcessor {
+                handler,
+                cursor: Default::default(),
+                counters: Default::default(),
+                work_sequence: work_sequence.clone(),
+                _marker: Default::default(),
+            })
+            .collect()
+    }
+}
+
+struct WorkProcessor<E, T> {
+    handler: E,
+    cursor: Arc<AtomicSequence>,
+    counters: Arc<ProcessorCounters>,
+    work_sequence: Arc<AtomicSequence>,
+    _marker: PhantomData<T>,
+}
+
+struct RunnableWorkProcessor<E, T, D: DataProvider<T>, B: SequenceBarrier> {
+    processor: WorkProcessor<E, T>,
+    data_provider: Arc<D>,
+    barrier: B,
+    available: Sequence,
+    next: Sequence,
+    processed: bool,
+}
+
+impl<'a, E, T> EventProcessorMut<'a, T> for WorkProcessor<E, T>
+where
+    E: WorkHandler<T> + Send + 'a,
+    T: Send + 'a,
+{
+    fn prepare<B: SequenceBarrier + 'a, D: DataProvider<T> + 'a>(
+        self,
+        barrier: B,
+        data_provider: Arc<D>,
+    ) -> Box<dyn Runnable + 'a> {
+        Box::new(RunnableWorkProcessor {
+            processor: self,
+            data_provider,
+            barrier,
+            available: -1,
+            next: 0,
+            processed: true,
+        })
+    }
+
+    fn get_cursor(&self) -> Arc<AtomicSequence> {
+        self.cursor.clone()
+    }
+
+    fn get_counters(&self) -> Arc<ProcessorCounters> {
+        self.counters.clone()
+    }
+}
+
+impl<'a, E, T> EventProcessor<'a, T> for WorkProcessor<E, T>
+where
+    E: WorkHandler<T> + Send + 'a,
+    T: Send + 'a,
+{
+}
+
+impl<E, T, D, B> RunnableWorkProcessor<E, T, D, B>
+where
+    E: WorkHandler<T> + Send,
+    D: DataProvider<T>,
+    B: SequenceBarrier,
+    T: Send,
+{
+    fn advance(&mut self, blocking: bool) -> StepState {
+        let f = &mut self.processor.handler;
+        let cursor = &self.processor.cursor;
+        let work_sequence = &self.processor.work_sequence;
+        let data_provider = &self.data_provider;
+        let barrier = &self.barrier;
+        let _guard = HaltOnPanic::new(barrier);
+
+        loop {
+            if self.processed {
+                self.processed = false;
+                loop {
+                    self.next = work_sequence.get() + 1;
+                    cursor.set(self.next - 1);
+                    if work_sequence.compare_exchange(self.next - 1, self.next) {
+                        break;
+                    }
+                }
+            }
+
+            if self.available >= self.next {
+                if !barrier.is_invalid(self.next) {
+                    let value = unsafe { data_provider.get(self.next) };
+                    f.handle_event(value, self.next);
+                }
+                self.processed = true;
+                self.processor.counters.record_batch(1);
+                return StepState::Processed;
+            }
+
+            barrier.signal();
+            if blocking {
+                self.available = match barrier.wait_for(self.next) {
+                    Ok(seq) => seq,
+                    Err(WaitError::Timeout) => continue,
+                    Err(WaitError::Alerted) => return StepState::Halted,
+                };
+            } else if barrier.is_alerted() {
+                return StepState::Halted;
+            } else {
+                self.available = barrier.available();
+                if self.available < self.next {
+                    return StepState::Idle;
+                }
+            }
+        }
+    }
+}
+
+impl<E, T, D, B> Runnable for RunnableWorkProcessor<E, T, D, B>
+where
+    E: WorkHandler<T> + Send,
+    D: DataProvider<T>,
+    B: SequenceBarrier,
+    T: Send,
+{
+    fn run(mut self: Box<Self>) {
+        self.start();
+        while self.advance(true) != StepState::Halted {}
+        self.shutdown();
+    }
+
+    fn start(&mut self) {
+        let _guard = HaltOnPanic::new(&self.barrier);
+        self.available = self.processor.cursor.get();
+        self.next = self.available + 1;
+        self.processor.handler.on_start();
+    }
+
+    fn step(&mut self) -> StepState {
+        self.advance(false)
+    }
+
+    fn name(&self) -> String {
+        short_type_name::<E>()
+    }
+
+    fn get_cursor(&self) -> Arc<AtomicSequence> {
+        self.processor.cursor.clone()
+    }
+
+    fn shutdown(&mut self) {
+        let _guard = HaltOnPanic::new(&self.barrier);
+        self.processor.handler.on_shutdown();
+    }
+}
@@ -32,7 +32,8 @@ fn main() {
             .with_spin_wait()
             .with_single_producer()
             .with_barrier(|_| {})
-            .build();
+            .build()
+            .unwrap();
 
         producer.write(std::iter::once(()), |slot, _seq, _item| loop {
             // Repeatedly write Ref(&addr) and Int(0xdeadbeef) into the cell.
@@ -45,7 +46,8 @@ fn main() {
         .with_spin_wait()
         .with_single_producer()
         .with_barrier(|_| {})
-        .build();
+        .build()
+        .unwrap();
 
     producer.write(std::iter::once(()), |slot, _seq, _item| {
         loop {
@@ -0,0 +1,32 @@
+#![forbid(unsafe_code)]
+
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use disrustor::{DisrustorBuilder, EventHandler, EventProcessorExecutor, Sequence};
+
+struct Borrowing<'a> {
+    last: &'a AtomicI64,
+}
+
+impl<'a> EventHandler<i64> for Borrowing<'a> {
+    fn handle_event(&mut self, _event: &i64, sequence: Sequence, _eob: bool) {
+        self.last.store(sequence, Ordering::Release);
+    }
+}
+
+fn main() {
+    let last = AtomicI64::new(-1);
+
+    let (executor, _producer) = DisrustorBuilder::with_ring_buffer(4)
+        .with_spin_wait()
+        .with_single_producer()
+        .with_barrier(|b| {
+            b.handle_events(Borrowing { last: &last });
+        })
+        .build()
+        .unwrap();
+
+    // Leaking the handle skips the join, so the consumer thread would
+    // outlive `last` and keep writing through a dangling reference.
+    std::mem::forget(executor.spawn());
+}